@@ -0,0 +1,175 @@
+use crate::lexer::{Spacing, Token};
+
+/*
+Abstract syntax tree built by the MarkdownSyntaxAnalyzer.
+The SemanticAnalyzer walks this tree for the static checks and for the HTML,
+so both phases see the exact same structure.
+Leaf nodes keep their Token so diagnostics can still point at the source.
+ */
+
+//<lolcode> ::= HAI <comments> <head> <body> KTHXBYE
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Document {
+    pub comments: Vec<Comment>,
+    pub head: Head,
+    pub body: Vec<Block>,
+}
+
+//<head> ::= MAEK HEAD <title> OIC
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Head {
+    //words of <title> ::= GIMMEH TITLE TEXT MKAY
+    pub title: Vec<Token>,
+}
+
+//<comment> ::= OBTW TEXT TLDR
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Comment {
+    pub words: Vec<Token>,
+}
+
+//<variable-define> ::= IHAZ VARDEF ITIZ <value> MKAY
+//<value> ::= (TEXT | LITERAL)+
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VariableDefine {
+    pub name: Token,
+    pub value: Vec<Token>,
+}
+
+impl VariableDefine {
+    //the words of the value with the source whitespace between them, quoted literals are kept as written
+    pub fn value_text(&self) -> String {
+        words_text(&self.value)
+    }
+}
+
+//joins words with the (normalised) whitespace that separated them in the source
+pub fn words_text(words: &[Token]) -> String {
+    let mut text = String::new();
+    for (i, word) in words.iter().enumerate() {
+        if i > 0 {
+            match word.space_before {
+                Spacing::Glued => {}
+                Spacing::Space => text.push(' '),
+                Spacing::Newline => text.push('\n'),
+            }
+        }
+        text.push_str(&word.text);
+    }
+    text
+}
+
+//<variable-use> ::= LEMME SEE VAR_NAME MKAY
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct VariableUse {
+    pub name: Token,
+}
+
+//<paragraph> ::= MAEK PARAGRAF <variable-define> <inner-paragraph> OIC
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Paragraph {
+    pub define: Option<VariableDefine>,
+    pub content: Vec<Inline>,
+}
+
+//<list> ::= MAEK LIST <list-items> OIC | MAEK NUMBRD LIST <list-items> OIC
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct List {
+    //NUMBRD, <ol> instead of <ul>
+    pub ordered: bool,
+    pub items: Vec<ListItem>,
+}
+
+//<list-item> ::= GIMMEH ITEM <inner-list> <list>? MKAY
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct ListItem {
+    pub content: Vec<Inline>,
+    //a nested list comes after the item's own text
+    pub sublist: Option<List>,
+}
+
+//<heading> ::= GIMMEH HEADIN TEXT <inline>+ MKAY
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Heading {
+    //checked to be 1 to 6 by the SemanticAnalyzer
+    pub level: Token,
+    pub content: Vec<Inline>,
+}
+
+//<table> ::= MAEK TABL <header-row>? <row>* OIC
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Table {
+    pub header: Option<TableRow>,
+    pub rows: Vec<TableRow>,
+}
+
+//<header-row> ::= MAEK HEDR <cell>* OIC, <row> ::= MAEK ROW <cell>* OIC
+//<cell> ::= GIMMEH CELL <inline>* MKAY
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct TableRow {
+    //the HEDR/ROW keyword, where cell count errors point
+    pub keyword: Token,
+    pub cells: Vec<Vec<Inline>>,
+}
+
+//<code-block> ::= MAEK CODEZ TEXT? CODE_TEXT OIC
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CodeBlock {
+    pub language: Option<Token>,
+    //exactly as written, whitespace included
+    pub code: Token,
+}
+
+//<quote> ::= MAEK QUOTE <inline>* <cite>? OIC
+//<cite> ::= GIMMEH CITE <inline>* MKAY
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Quote {
+    pub content: Vec<Inline>,
+    pub cite: Option<Vec<Inline>>,
+}
+
+//<inner-body>, everything that can appear directly in the body
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Block {
+    Paragraph(Paragraph),
+    List(List),
+    Heading(Heading),
+    Table(Table),
+    Code(CodeBlock),
+    Quote(Quote),
+    //<line> ::= GIMMEH LINE MKAY
+    Line,
+    //<preformatted> ::= MAEK PREFORMATTD <inline>* OIC
+    //whitespace in between is kept exactly, as Text holding only the whitespace
+    Preformatted(Vec<Inline>),
+    Comment(Comment),
+    VariableDefine(VariableDefine),
+    Inline(Inline),
+}
+
+//<inline>, everything that can appear inside a paragraph, bold and italics nest freely
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Inline {
+    Text(Token),
+    //<bold> ::= GIMMEH BOLD <inline>+ MKAY
+    Bold(Vec<Inline>),
+    //<italics> ::= GIMMEH ITALICS <inline>+ MKAY
+    Italics(Vec<Inline>),
+    VariableUse(VariableUse),
+    //whitespace between two items, never first or last in a list
+    Space(Spacing),
+    //<newline> ::= GIMMEH NEWLINE
+    Newline,
+    //<raw> ::= GIMMEH RAW TEXT MKAY, words are emitted exactly as written
+    Raw(Vec<Token>),
+    //<audio> ::= GIMMEH SOUNDZ ADDRESS MKAY
+    Audio(Token),
+    //<video> ::= GIMMEH VIDZ ADDRESS MKAY
+    Video(Token),
+    //<link> ::= GIMMEH LINKZ ADDRESS <inline>* MKAY, no text shows the address itself
+    Link { address: Token, content: Vec<Inline> },
+    //<code> ::= GIMMEH CODE CODE_TEXT MKAY
+    Code(Token),
+    //<image> ::= GIMMEH PIKCHUR ADDRESS TEXT* MKAY, the words are the alt text
+    Image { address: Token, alt: Vec<Token> },
+}
@@ -0,0 +1,150 @@
+use std::fmt;
+
+//line and column of a lexeme in the source, both start at 1
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct Position {
+    pub line: usize,
+    pub column: usize,
+}
+
+impl Default for Position {
+    fn default() -> Self {
+        Self { line: 1, column: 1 }
+    }
+}
+
+/*
+One diagnostic: where it happened (file, line, column), the lexeme
+that caused it, and the message itself.
+ */
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Diagnostic {
+    pub file: String,
+    pub line: usize,
+    pub column: usize,
+    pub lexeme: String,
+    pub message: String,
+}
+
+impl Diagnostic {
+    pub fn new(file: &str, position: Position, lexeme: &str, message: impl Into<String>) -> Self {
+        Self {
+            file: file.to_string(),
+            line: position.line,
+            column: position.column,
+            lexeme: lexeme.to_string(),
+            message: message.into(),
+        }
+    }
+}
+
+impl fmt::Display for Diagnostic {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}:{}:{}", self.file, self.line, self.column)?;
+        //the end of the file has no lexeme to point at
+        if self.lexeme.is_empty() {
+            write!(f, ": at end of file")
+        } else {
+            write!(f, ": near '{}'", self.lexeme)
+        }
+    }
+}
+
+/*
+Error type shared by every phase of the compiler.
+Before this, every phase printed its own message and called std::process::exit(1),
+now the diagnostic is handed back up to whoever called compile_to_html.
+ */
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum CompileError {
+    //unknown lexeme / misused '#'
+    Lexical(Diagnostic),
+    //input does not follow the grammar
+    Syntax(Diagnostic),
+    //static semantic errors like an undefined variable
+    Semantic(Diagnostic),
+    //every error the parser collected while recovering, in source order (from_errors only builds it for 2 or more)
+    Multiple(Vec<CompileError>),
+}
+
+impl CompileError {
+    //exit code the lolcompiler binary uses for this kind of error
+    pub fn exit_code(&self) -> i32 {
+        match self {
+            CompileError::Lexical(_) => 2,
+            CompileError::Syntax(_) => 3,
+            CompileError::Semantic(_) => 4,
+            CompileError::Multiple(errors) => errors.first().map_or(1, CompileError::exit_code),
+        }
+    }
+
+    //the diagnostic of this error, or of the first one for Multiple (None if that is empty)
+    pub fn diagnostic(&self) -> Option<&Diagnostic> {
+        match self {
+            CompileError::Lexical(d) | CompileError::Syntax(d) | CompileError::Semantic(d) => Some(d),
+            CompileError::Multiple(errors) => errors.first().and_then(CompileError::diagnostic),
+        }
+    }
+
+    //wraps the collected errors, a single one is returned as is and no errors gives None
+    pub fn from_errors(mut errors: Vec<CompileError>) -> Option<Self> {
+        match errors.len() {
+            0 => None,
+            1 => errors.pop(),
+            _ => Some(CompileError::Multiple(errors)),
+        }
+    }
+
+    //number of errors this stands for
+    pub fn count(&self) -> usize {
+        match self {
+            CompileError::Multiple(errors) => errors.len(),
+            _ => 1,
+        }
+    }
+}
+
+impl fmt::Display for CompileError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let (kind, d) = match self {
+            CompileError::Lexical(d) => ("Lexical error", d),
+            CompileError::Syntax(d) => ("Syntax error", d),
+            CompileError::Semantic(d) => ("Static semantic error", d),
+            //one error per line, then the summary count
+            CompileError::Multiple(errors) => {
+                for e in errors {
+                    writeln!(f, "{}", e)?;
+                }
+                return write!(f, "{} errors found", errors.len());
+            }
+        };
+        write!(f, "{}: {}: {}", d, kind, d.message)
+    }
+}
+
+impl std::error::Error for CompileError {}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn syntax(column: usize) -> CompileError {
+        CompileError::Syntax(Diagnostic::new("t.lol", Position { line: 1, column }, "#MKAY", "oops"))
+    }
+
+    #[test]
+    fn from_errors_only_wraps_two_or_more() {
+        assert_eq!(CompileError::from_errors(Vec::new()), None);
+        assert_eq!(CompileError::from_errors(vec![syntax(1)]), Some(syntax(1)));
+        let both = CompileError::from_errors(vec![syntax(1), syntax(7)]).unwrap();
+        assert_eq!(both.count(), 2);
+        assert_eq!(both.diagnostic().map(|d| d.column), Some(1));
+    }
+
+    #[test]
+    fn messages_show_where_and_near_what() {
+        assert_eq!(syntax(4).to_string(), "t.lol:1:4: near '#MKAY': Syntax error: oops");
+        let eof = CompileError::Syntax(Diagnostic::new("t.lol", Position { line: 2, column: 1 }, "", "oops"));
+        assert_eq!(eof.to_string(), "t.lol:2:1: at end of file: Syntax error: oops");
+    }
+}
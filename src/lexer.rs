@@ -0,0 +1,883 @@
+use std::fmt;
+use std::ops::Range;
+
+use crate::error::{CompileError, Diagnostic, Position};
+
+/*
+What kind of lexeme a token is. The lexer classifies every lexeme once,
+so the parser and semantic analyzer can match on this instead of comparing strings.
+ */
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TokenKind {
+    Hai,
+    Kthxbye,
+    Obtw,
+    Tldr,
+    Maek,
+    Oic,
+    Gimmeh,
+    Mkay,
+    Head,
+    Title,
+    Paragraf,
+    Bold,
+    Italics,
+    List,
+    //#MAEK NUMBRD LIST, an ordered list
+    Numbrd,
+    //#MAEK CODEZ [language] ... #OIC and #GIMMEH CODE ... #MKAY
+    Codez,
+    Code,
+    //#MAEK QUOTE ... #GIMMEH CITE ... #MKAY #OIC
+    Quote,
+    Cite,
+    //#GIMMEH LINE #MKAY
+    Line,
+    //#MAEK PREFORMATTD ... #OIC
+    Preformattd,
+    //#MAEK TABL, #MAEK HEDR / #MAEK ROW inside it, #GIMMEH CELL inside those
+    Tabl,
+    Hedr,
+    Row,
+    Cell,
+    Item,
+    Newline,
+    //#GIMMEH HEADIN <level> ... #MKAY
+    Headin,
+    //#GIMMEH RAW ... #MKAY, words in between are plain text
+    Raw,
+    Soundz,
+    Vidz,
+    //#GIMMEH LINKZ <address> text #MKAY
+    Linkz,
+    //#GIMMEH PIKCHUR <address> alt text #MKAY
+    Pikchur,
+    //#I HAZ, #IT IZ and #LEMME SEE are two tokens each
+    I,
+    Haz,
+    It,
+    Iz,
+    Lemme,
+    See,
+    //plain words, and the word right after SOUNDZ/VIDZ/LINKZ/PIKCHUR
+    Text,
+    Address,
+    //"quoted string literal" in a variable value, text is without the quotes
+    Literal,
+    //everything inside CODEZ/CODE, whitespace and '#' included
+    CodeText,
+    //end of input
+    #[default]
+    Eof,
+}
+
+/*
+What the lexer is currently reading. Comments, titles and addresses are verbatim:
+every word up to the closing keyword is text, even if it starts with '#'.
+ */
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+enum LexMode {
+    #[default]
+    Normal,
+    //#OBTW ... #TLDR
+    Comment,
+    //#GIMMEH TITLE ... #MKAY
+    Title,
+    //#GIMMEH SOUNDZ/VIDZ ... #MKAY
+    Address,
+    //#GIMMEH RAW ... #MKAY
+    Raw,
+    //the one address word after #GIMMEH LINKZ/PIKCHUR, the text after it is lexed normally
+    Link,
+    //#IT IZ ... #MKAY, where quoted string literals are allowed
+    Value,
+    //#MAEK CODEZ and #GIMMEH CODE, read char by char by read_code
+    CodeBlock,
+    Code,
+}
+
+impl LexMode {
+    //keyword that ends a verbatim mode, None if words are lexed normally
+    fn closing_keyword(self) -> Option<&'static str> {
+        match self {
+            LexMode::Comment => Some("#TLDR"),
+            LexMode::Title | LexMode::Address | LexMode::Raw | LexMode::Link => Some("#MKAY"),
+            LexMode::Normal | LexMode::Value | LexMode::CodeBlock | LexMode::Code => None,
+        }
+    }
+}
+
+/*
+The whitespace in front of a token, normalised: any run of whitespace with a line
+break in it is a Newline, any other run is a Space, and no whitespace at all is Glued
+(punctuation split off a keyword, see push_glued).
+ */
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Spacing {
+    #[default]
+    Glued,
+    Space,
+    Newline,
+}
+
+/*
+A single lexeme and where it came from in the source.
+span is the byte range into the source string, line/column start at 1.
+ */
+#[derive(Debug, Clone, PartialEq, Eq, Default)]
+pub struct Token {
+    pub kind: TokenKind,
+    pub text: String,
+    pub span: Range<usize>,
+    pub line: usize,
+    pub column: usize,
+    //whitespace between this token and the one before it
+    pub space_before: Spacing,
+    //the same whitespace exactly as written, for #MAEK PREFORMATTD
+    pub whitespace_before: String,
+}
+
+impl Token {
+    pub fn new(kind: TokenKind, text: impl Into<String>, span: Range<usize>, position: Position) -> Self {
+        Self {
+            kind,
+            text: text.into(),
+            span,
+            line: position.line,
+            column: position.column,
+            space_before: Spacing::Glued,
+            whitespace_before: String::new(),
+        }
+    }
+
+    pub fn position(&self) -> Position {
+        Position { line: self.line, column: self.column }
+    }
+
+    //end of input
+    pub fn is_empty(&self) -> bool {
+        self.kind == TokenKind::Eof
+    }
+}
+
+impl fmt::Display for Token {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(&self.text)
+    }
+}
+
+/// Trait for a simple lexical analyzer.
+/// Implements a character-by-character analysis
+/// from a state machine design.
+pub trait LexicalAnalyzer {
+/// Return the next character from the input.
+/// If input is exhausted, returns '\0'.
+fn get_char(&mut self) -> char;
+/// Add a character to the current potential token.
+fn add_char(&mut self, c: char);
+/// Lookup a potential token to determine if it is valid.
+/// Returns true if a valid token/lexeme, false otherwise.
+fn lookup(&self, s: &str) -> bool;
+}
+
+/*
+
+This is the structure for the lexical analyzer. I based mine off
+the one used in assignment 5.
+
+Each terminal symbol is represented by its own token, as per the 
+given example ANTLR/BNF code.
+*/
+pub struct MarkdownLexicalAnalyzer {
+    //input chars, current read pos, current token build,d and list of tokens
+    input: Vec<char>,
+    position: usize,
+    current_build: String,
+    pub tokens: Vec<Token>, 
+    //file name used in diagnostics
+    pub file_name: String,
+    //byte offset and line/column of the next char to read
+    offset: usize,
+    line: usize,
+    column: usize,
+    //where the token being built starts
+    build_offset: usize,
+    build_start: Position,
+    build_spacing: Spacing,
+    build_whitespace: String,
+    //whitespace seen since the last token ended, normalised and as written
+    gap: Spacing,
+    gap_text: String,
+    //current lexer mode, switched by the keywords that open and close each construct
+    mode: LexMode,
+    //these are mostly used as placeholders
+    pub vardef: Vec<String>,
+    pub varval: Vec<String>,
+    pub text: Vec<String>,
+    pub address: Vec<String>,
+}
+
+/* 
+
+Implementation of the Markdown Lexical Analyzer, contains helper methods for token operatiosn.
+Here, again similar to assignment #5, I made the constructor with the same format
+The keywords themselves are in one table, see keyword() below
+ */
+impl MarkdownLexicalAnalyzer {
+    pub fn new(source: &str) -> Self{
+        Self {
+            input: source.chars().collect(),
+            position: 0,
+            current_build: String::new(),
+            tokens: Vec::new(),
+            file_name: "<input>".into(),
+            offset: 0,
+            line: 1,
+            column: 1,
+            build_offset: 0,
+            build_start: Position::default(),
+            build_spacing: Spacing::Glued,
+            build_whitespace: String::new(),
+            gap: Spacing::Glued,
+            gap_text: String::new(),
+            mode: LexMode::Normal,
+            vardef: Vec::new(),
+            varval: Vec::new(),
+            text: Vec::new(),
+            address: Vec::new(),
+        }
+    }
+
+    /*
+      Tokenize reads source code char by char, and translates it into  actual tokens like #HAI, #MAEK, etc.
+      It stores these tokens in a self.tokens for the parser to understand later.
+      Also similar to assignment 5.
+     */
+    pub fn tokenize(&mut self) -> Result<(), CompileError> {
+        //we're assuming get_char() returns a valid char here
+        loop {
+            //code is read raw, the normal rules don't apply until its closing keyword
+            if matches!(self.mode, LexMode::CodeBlock | LexMode::Code) {
+                self.read_code()?;
+                continue;
+            }
+
+            let offset = self.offset;
+            let pos = self.current_position();
+            let c = self.get_char();
+            if c == '\0' {
+                break;
+            }
+
+            if c.is_whitespace() {
+                self.add_gap(c);
+                if !self.current_build.is_empty() {
+                    //finish token and push it
+                    self.finish_token(offset)?;
+                }
+            } else if c == '"' && self.mode == LexMode::Value && self.current_build.is_empty() {
+                //quoted value, read it whole so spaces and '#' inside are kept
+                self.build_offset = offset;
+                self.build_start = pos;
+                self.build_spacing = std::mem::take(&mut self.gap);
+                self.build_whitespace = std::mem::take(&mut self.gap_text);
+                self.read_literal()?;
+            } else {
+                //remember where the token starts for diagnostics
+                if self.current_build.is_empty() {
+                    self.build_offset = offset;
+                    self.build_start = pos;
+                    self.build_spacing = std::mem::take(&mut self.gap);
+                    self.build_whitespace = std::mem::take(&mut self.gap_text);
+                }
+                //if not whitespace, just append character to current build
+                self.add_char(c); 
+            }
+        }
+
+        //after the loop, check one last time if token was being built
+        if !self.current_build.is_empty() {
+            self.finish_token(self.offset)?;
+        }
+        //then reverse the list with .reverse()
+        self.tokens.reverse(); 
+        Ok(())
+    }
+
+    /*
+    Finishes the token being built, which ends at byte offset end, and pushes it.
+    Every word goes through here, the last one in the file included, so they all follow the same rules.
+     */
+    fn finish_token(&mut self, end: usize) -> Result<(), CompileError> {
+        let last_token = std::mem::take(&mut self.current_build);
+        let span = self.build_offset..end;
+
+        //punctuation glued to a keyword, like #MKAY. or (#GIMMEH
+        if self.push_glued(&last_token, end) {
+            return Ok(());
+        }
+        //verbatim text never needs the '#' check
+        if self.is_verbatim(&last_token) {
+            let kind = self.verbatim_kind();
+            self.push_token(kind, last_token, span);
+            return Ok(());
+        }
+        //backslash escape, \#HAI is the text #HAI
+        if let Some(text) = unescape(&last_token) {
+            self.push_token(TokenKind::Text, text, span);
+            return Ok(());
+        }
+
+        //checks to see if lexical error is detected
+        //every # must be followed by a recognized keyword
+        if last_token.starts_with('#') && !self.lookup(&last_token) {
+            return Err(self.lexical_error(&last_token));
+        }
+
+        //no error, then classify it and push it with its span
+        let kind = self.classify(&last_token);
+        self.push_token(kind, last_token, span);
+        Ok(())
+    }
+
+    //pushes a finished token and switches mode on the keywords that open or close one
+    fn push_token(&mut self, kind: TokenKind, text: String, span: Range<usize>) {
+        let previous = self.tokens.last().map(|t| t.kind);
+        match kind {
+            //a stray #CODE or #TITLE in the text is still a keyword (the parser reports it),
+            //but only the one right after its #GIMMEH/#MAEK/#IT opens a mode
+            TokenKind::Title | TokenKind::Raw | TokenKind::Code | TokenKind::Soundz | TokenKind::Vidz
+                | TokenKind::Linkz | TokenKind::Pikchur if previous != Some(TokenKind::Gimmeh) => {}
+            TokenKind::Codez if previous != Some(TokenKind::Maek) => {}
+            TokenKind::Iz if previous != Some(TokenKind::It) => {}
+            TokenKind::Obtw => self.mode = LexMode::Comment,
+            TokenKind::Title => self.mode = LexMode::Title,
+            TokenKind::Raw => self.mode = LexMode::Raw,
+            TokenKind::Codez => self.mode = LexMode::CodeBlock,
+            TokenKind::Code => self.mode = LexMode::Code,
+            TokenKind::Soundz | TokenKind::Vidz => self.mode = LexMode::Address,
+            TokenKind::Linkz | TokenKind::Pikchur => self.mode = LexMode::Link,
+            //only one verbatim word after LINKZ/PIKCHUR
+            TokenKind::Address if self.mode == LexMode::Link => self.mode = LexMode::Normal,
+            TokenKind::Iz => self.mode = LexMode::Value,
+            TokenKind::Tldr | TokenKind::Mkay => self.mode = LexMode::Normal,
+            _ => {}
+        }
+        let mut token = Token::new(kind, text, span, self.build_start);
+        token.space_before = self.build_spacing;
+        token.whitespace_before = std::mem::take(&mut self.build_whitespace);
+        self.tokens.push(token);
+    }
+
+    //a line break anywhere in the gap wins over plain spaces
+    fn add_gap(&mut self, c: char) {
+        self.gap_text.push(c);
+        if c == '\n' {
+            self.gap = Spacing::Newline;
+        } else if self.gap == Spacing::Glued {
+            self.gap = Spacing::Space;
+        }
+    }
+
+    /*
+    Splits a word like "(#GIMMEH" or "#MKAY)." into the punctuation and the keyword
+    and pushes the pieces, every piece after the first is Glued to the one before.
+    Returns false (and pushes nothing) if the word isn't punctuation around a keyword.
+    In a verbatim mode only the closing keyword is split off, everything else stays text.
+     */
+    fn push_glued(&mut self, word: &str, end: usize) -> bool {
+        let Some((prefix, keyword, suffix)) = self.split_glued(word) else {
+            return false;
+        };
+        if self.mode.closing_keyword().is_some_and(|closing| !closing.eq_ignore_ascii_case(keyword)) {
+            return false;
+        }
+
+        let mut start = self.build_offset;
+        let mut column = self.build_start.column;
+        let line = self.build_start.line;
+        for (i, piece) in [prefix, keyword, suffix].into_iter().enumerate() {
+            if piece.is_empty() {
+                continue;
+            }
+            let kind = match i {
+                1 => self.classify(piece),
+                _ if self.mode.closing_keyword().is_some() => self.verbatim_kind(),
+                _ => TokenKind::Text,
+            };
+            let piece_end = if i == 2 { end } else { start + piece.len() };
+            self.build_start = Position { line, column };
+            self.push_token(kind, piece.to_string(), start..piece_end);
+            self.build_spacing = Spacing::Glued;
+            start = piece_end;
+            column += piece.chars().count();
+        }
+        true
+    }
+
+    //prefix punctuation, #KEYWORD, suffix punctuation; at least one side has to be non-empty
+    fn split_glued<'a>(&self, word: &'a str) -> Option<(&'a str, &'a str, &'a str)> {
+        let is_punctuation = |c: char| c.is_ascii_punctuation() && c != '#' && c != '\\' && c != '"';
+        let hash = word.find('#')?;
+        let (prefix, rest) = word.split_at(hash);
+        let keyword_len = rest[1..].find(|c: char| !c.is_ascii_alphanumeric()).map_or(rest.len(), |i| i + 1);
+        let (keyword, suffix) = rest.split_at(keyword_len);
+
+        if prefix.is_empty() && suffix.is_empty() {
+            return None;
+        }
+        if !prefix.chars().all(is_punctuation) || !suffix.chars().all(is_punctuation) {
+            return None;
+        }
+        if !self.lookup(keyword) {
+            return None;
+        }
+        Some((prefix, keyword, suffix))
+    }
+
+    //inside a comment, title or address, anything but the closing keyword is text
+    fn is_verbatim(&self, lexeme: &str) -> bool {
+        match self.mode.closing_keyword() {
+            Some(closing) => !closing.eq_ignore_ascii_case(lexeme),
+            None => false,
+        }
+    }
+
+    //kind of a verbatim word, the first word after SOUNDZ/VIDZ/LINKZ/PIKCHUR is the address
+    fn verbatim_kind(&self) -> TokenKind {
+        match self.tokens.last().map(|t| t.kind) {
+            Some(TokenKind::Soundz | TokenKind::Vidz | TokenKind::Linkz | TokenKind::Pikchur) => TokenKind::Address,
+            _ => TokenKind::Text,
+        }
+    }
+
+    /*
+    Reads a "quoted string literal" after the opening quote has been consumed.
+    Everything up to the closing quote is kept as is, whitespace and '#' included.
+    The literal has to be followed by whitespace or the end of the input.
+     */
+    fn read_literal(&mut self) -> Result<(), CompileError> {
+        let mut literal = String::new();
+        loop {
+            match self.get_char() {
+                '\0' => return Err(self.literal_error("unterminated string literal")),
+                '"' => break,
+                c => literal.push(c),
+            }
+        }
+        let end = self.offset;
+        match self.get_char() {
+            '\0' => {}
+            c if c.is_whitespace() => self.add_gap(c),
+            _ => return Err(self.literal_error("string literal must be followed by whitespace")),
+        }
+        self.push_token(TokenKind::Literal, literal, self.build_offset..end);
+        Ok(())
+    }
+
+    /*
+    Called right after CODEZ or CODE was pushed (and the whitespace after it read).
+    A code block takes an optional language on the same line as CODEZ, its code starts
+    on the next line and runs up to #OIC. Inline code runs up to #MKAY.
+     */
+    fn read_code(&mut self) -> Result<(), CompileError> {
+        let block = self.mode == LexMode::CodeBlock;
+        self.gap_text.clear();
+        if block && self.gap != Spacing::Newline {
+            self.read_code_language()?;
+        }
+        let closing = if block { "#OIC" } else { "#MKAY" };
+        let (mut code, span, start, closing_offset, closing_pos) = self.read_raw(closing)?;
+
+        //the line break/indentation in front of #OIC and the space in front of #MKAY aren't code
+        if block {
+            code.truncate(code.trim_end_matches([' ', '\t', '\r']).len());
+            if code.ends_with('\n') {
+                code.pop();
+            }
+        } else if code.ends_with(char::is_whitespace) {
+            code.pop();
+        }
+
+        self.build_start = start;
+        self.build_spacing = Spacing::Glued;
+        self.push_token(TokenKind::CodeText, code, span);
+
+        self.build_start = closing_pos;
+        self.build_spacing = Spacing::Space;
+        let kind = if block { TokenKind::Oic } else { TokenKind::Mkay };
+        self.push_token(kind, closing.to_string(), closing_offset..self.offset);
+        self.mode = LexMode::Normal;
+        self.gap = Spacing::Glued;
+        self.gap_text.clear();
+        Ok(())
+    }
+
+    //the optional language word after CODEZ, nothing else may follow it on that line
+    fn read_code_language(&mut self) -> Result<(), CompileError> {
+        self.skip_blanks();
+        if self.peek_char().is_some_and(|c| c != '\n') {
+            self.build_offset = self.offset;
+            self.build_start = self.current_position();
+            self.build_spacing = Spacing::Space;
+            let mut language = String::new();
+            while let Some(c) = self.peek_char().filter(|c| !c.is_whitespace()) {
+                language.push(c);
+                self.get_char();
+            }
+            self.push_token(TokenKind::Text, language, self.build_offset..self.offset);
+            self.skip_blanks();
+        }
+        match self.peek_char() {
+            Some('\n') => {
+                self.get_char();
+                Ok(())
+            }
+            None => Ok(()),
+            Some(_) => Err(CompileError::Lexical(Diagnostic::new(&self.file_name, self.current_position(), "CODEZ", "the code of #MAEK CODEZ has to start on a new line"))),
+        }
+    }
+
+    /*
+    Reads chars up to the closing keyword standing on its own (whitespace in front of it,
+    whitespace, punctuation or the end of the input after it). Returns the text before it with its span and position, and where the keyword starts.
+     */
+    fn read_raw(&mut self, closing: &str) -> Result<(String, Range<usize>, Position, usize, Position), CompileError> {
+        let start_offset = self.offset;
+        let start = self.current_position();
+        let mut raw = String::new();
+        //where every char read so far starts, so the closing keyword gets the right position
+        let mut starts: Vec<(usize, Position)> = Vec::new();
+        loop {
+            let offset = self.offset;
+            let pos = self.current_position();
+            let c = self.get_char();
+            if c == '\0' {
+                let message = format!("code is missing its closing {}", closing);
+                return Err(CompileError::Lexical(Diagnostic::new(&self.file_name, start, closing, message)));
+            }
+            raw.push(c);
+            starts.push((offset, pos));
+
+            let Some(before) = raw.len().checked_sub(closing.len()) else {
+                continue;
+            };
+            //get() is None when before lands inside a multi-byte char, so no match then
+            if !raw.get(before..).is_some_and(|tail| tail.eq_ignore_ascii_case(closing)) {
+                continue;
+            }
+            let free_before = raw[..before].chars().last().is_none_or(char::is_whitespace);
+            //punctuation may be glued after it, like #MKAY. (see push_glued)
+            let free_after = self.peek_char().is_none_or(|c| c.is_whitespace() || (c.is_ascii_punctuation() && c != '#'));
+            if free_before && free_after {
+                let (closing_offset, closing_pos) = starts[starts.len() - closing.len()];
+                raw.truncate(before);
+                return Ok((raw, start_offset..closing_offset, start, closing_offset, closing_pos));
+            }
+        }
+    }
+
+    //the char get_char would return next, without reading it
+    fn peek_char(&self) -> Option<char> {
+        self.input.get(self.position).copied()
+    }
+
+    //skips spaces and tabs, but not line breaks
+    fn skip_blanks(&mut self) {
+        while self.peek_char().is_some_and(|c| c.is_whitespace() && c != '\n') {
+            self.get_char();
+        }
+    }
+
+    //bad string literal starting at the token currently being built
+    fn literal_error(&self, message: &str) -> CompileError {
+        CompileError::Lexical(Diagnostic::new(&self.file_name, self.build_start, "\"", message))
+    }
+
+    //line/column of the next char get_char will return (end of input once exhausted)
+    pub fn current_position(&self) -> Position {
+        Position { line: self.line, column: self.column }
+    }
+
+    //empty token located at the end of the input, handed out once the stack is empty
+    pub fn eof_token(&self) -> Token {
+        Token::new(TokenKind::Eof, "", self.offset..self.offset, self.current_position())
+    }
+
+    /*
+    Classifies a lexeme that already passed the '#' check.
+    Second words (HEAD, BOLD, HAZ, ...) are only keywords right after the
+    annotation that takes them, so "list" inside a paragraph stays TEXT.
+    tokens is not reversed yet, so the last pushed token is the previous one.
+     */
+    fn classify(&self, s: &str) -> TokenKind {
+        let previous = self.tokens.last().map(|t| t.kind);
+        let Some(keyword) = keyword(s) else {
+            return TokenKind::Text;
+        };
+
+        match (previous, keyword) {
+            //#HAI, #MAEK, #GIMMEH and the rest always start an annotation
+            (_, k) if is_opener(k) => k,
+            (Some(TokenKind::Maek), k @ (TokenKind::Head | TokenKind::Paragraf | TokenKind::List | TokenKind::Numbrd
+                | TokenKind::Tabl | TokenKind::Hedr | TokenKind::Row | TokenKind::Codez
+                | TokenKind::Quote | TokenKind::Preformattd)) => k,
+            (Some(TokenKind::Numbrd), TokenKind::List) => TokenKind::List,
+            (Some(TokenKind::Gimmeh), k @ (TokenKind::Title | TokenKind::Bold | TokenKind::Italics
+                | TokenKind::Item | TokenKind::Newline | TokenKind::Raw | TokenKind::Headin | TokenKind::Cell | TokenKind::Code | TokenKind::Cite | TokenKind::Line | TokenKind::Soundz | TokenKind::Vidz | TokenKind::Linkz | TokenKind::Pikchur)) => k,
+            (Some(TokenKind::I), TokenKind::Haz) => TokenKind::Haz,
+            (Some(TokenKind::It), TokenKind::Iz) => TokenKind::Iz,
+            (Some(TokenKind::Lemme), TokenKind::See) => TokenKind::See,
+            //lookup accepts a '#' in front of these (e.g. #HEAD), keep that
+            (_, k) if s.starts_with('#') => k,
+            _ => TokenKind::Text,
+        }
+    }
+
+    //misused '#' at the start of the token currently being built
+    fn lexical_error(&self, lexeme: &str) -> CompileError {
+        CompileError::Lexical(Diagnostic::new(&self.file_name, self.build_start, lexeme, format!("'#' is misused in token {}", lexeme)))
+    }
+}
+
+
+/*
+The one table of keywords, ignoring case. The words that open an annotation
+(#HAI, #MAEK, #GIMMEH, #I ...) only count with their '#', the words after them
+(HEAD, BOLD, HAZ ...) are accepted with or without one.
+ */
+fn keyword(word: &str) -> Option<TokenKind> {
+    let upper = word.to_ascii_uppercase();
+    let kind = match upper.trim_start_matches('#') {
+        "HAI" => TokenKind::Hai,
+        "KTHXBYE" => TokenKind::Kthxbye,
+        "OBTW" => TokenKind::Obtw,
+        "TLDR" => TokenKind::Tldr,
+        "MAEK" => TokenKind::Maek,
+        "OIC" => TokenKind::Oic,
+        "GIMMEH" => TokenKind::Gimmeh,
+        "MKAY" => TokenKind::Mkay,
+        "I" => TokenKind::I,
+        "IT" => TokenKind::It,
+        "LEMME" => TokenKind::Lemme,
+        "HEAD" => TokenKind::Head,
+        "TITLE" => TokenKind::Title,
+        "PARAGRAF" => TokenKind::Paragraf,
+        "BOLD" => TokenKind::Bold,
+        "ITALICS" => TokenKind::Italics,
+        "LIST" => TokenKind::List,
+        "NUMBRD" => TokenKind::Numbrd,
+        "CODEZ" => TokenKind::Codez,
+        "CODE" => TokenKind::Code,
+        "QUOTE" => TokenKind::Quote,
+        "CITE" => TokenKind::Cite,
+        "LINE" => TokenKind::Line,
+        "PREFORMATTD" => TokenKind::Preformattd,
+        "TABL" => TokenKind::Tabl,
+        "HEDR" => TokenKind::Hedr,
+        "ROW" => TokenKind::Row,
+        "CELL" => TokenKind::Cell,
+        "ITEM" => TokenKind::Item,
+        "NEWLINE" => TokenKind::Newline,
+        "RAW" => TokenKind::Raw,
+        "HEADIN" => TokenKind::Headin,
+        "SOUNDZ" => TokenKind::Soundz,
+        "VIDZ" => TokenKind::Vidz,
+        "LINKZ" => TokenKind::Linkz,
+        "PIKCHUR" => TokenKind::Pikchur,
+        "HAZ" => TokenKind::Haz,
+        "IZ" => TokenKind::Iz,
+        "SEE" => TokenKind::See,
+        _ => return None,
+    };
+    (!is_opener(kind) || upper.starts_with('#')).then_some(kind)
+}
+
+//keywords that start an annotation on their own
+fn is_opener(kind: TokenKind) -> bool {
+    matches!(
+        kind,
+        TokenKind::Hai | TokenKind::Kthxbye | TokenKind::Obtw | TokenKind::Tldr | TokenKind::Maek | TokenKind::Oic
+            | TokenKind::Gimmeh | TokenKind::Mkay | TokenKind::I | TokenKind::It | TokenKind::Lemme
+    )
+}
+
+/*
+A word starting with \# or \\ is plain text with the first backslash dropped,
+so \#HAI or \#rustlang can be written in body text and \\#HAI gives \#HAI.
+Any other backslash, like in \d+ or a lone \, is kept as written.
+ */
+fn unescape(lexeme: &str) -> Option<String> {
+    match lexeme.strip_prefix('\\') {
+        Some(rest) if rest.starts_with('#') || rest.starts_with('\\') => Some(rest.to_string()),
+        _ => None,
+    }
+}
+
+
+/*
+Implements required behavior from trait
+Again, also similar to assignment 5. The funcs are reused and I use similar logic for lookup.
+*/
+impl LexicalAnalyzer for MarkdownLexicalAnalyzer {
+    //reads 1 char at a time from the inputted string
+    fn get_char(&mut self) -> char {
+        if self.position < self.input.len() {
+            let c = self.input[self.position];
+            self.position +=1;
+            self.offset += c.len_utf8();
+            //keep line/column in sync for diagnostics
+            if c == '\n' {
+                self.line += 1;
+                self.column = 1;
+            } else {
+                self.column += 1;
+            }
+            c
+        } else {
+            '\0' //if we're at the end of len, return the null character
+        }
+    }
+    
+    //add_char builds a token character by character
+    fn add_char(&mut self, c: char) {
+        self.current_build.push(c); //adds character to current build
+    }
+
+    //is the lexeme one of the keywords, same table classify uses
+    fn lookup(&self, s: &str) -> bool {
+        keyword(s).is_some()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    //tokenizes the source, tokens come back in reading order (the stack is reversed)
+    fn lex(source: &str) -> Vec<Token> {
+        let mut lexer = MarkdownLexicalAnalyzer::new(source);
+        lexer.tokenize().expect("source should lex");
+        lexer.tokens.into_iter().rev().collect()
+    }
+
+    fn kinds(tokens: &[Token]) -> Vec<TokenKind> {
+        tokens.iter().map(|t| t.kind).collect()
+    }
+
+    #[test]
+    fn keyword_table_needs_the_hash_only_on_openers() {
+        assert_eq!(keyword("#maek"), Some(TokenKind::Maek));
+        assert_eq!(keyword("MAEK"), None);
+        assert_eq!(keyword("#I"), Some(TokenKind::I));
+        assert_eq!(keyword("I"), None);
+        assert_eq!(keyword("paragraf"), Some(TokenKind::Paragraf));
+        assert_eq!(keyword("#Title"), Some(TokenKind::Title));
+        assert_eq!(keyword("#END"), None);
+    }
+
+    #[test]
+    fn quoted_value_keeps_spaces_and_hashes() {
+        let tokens = lex("#I HAZ x #IT IZ \"a  #b c\" #MKAY");
+        assert_eq!(kinds(&tokens)[3..], [TokenKind::It, TokenKind::Iz, TokenKind::Literal, TokenKind::Mkay]);
+        assert_eq!(tokens[5].text, "a  #b c");
+    }
+
+    #[test]
+    fn comments_titles_and_addresses_may_contain_hashes() {
+        let tokens = lex("#OBTW #1 fan #TLDR #GIMMEH TITLE C# notes #MKAY #GIMMEH SOUNDZ a.mp3#t=10 #MKAY");
+        assert_eq!(tokens[1].text, "#1");
+        assert_eq!(tokens[6].text, "C#");
+        assert_eq!(tokens[11].kind, TokenKind::Address);
+        assert_eq!(tokens[11].text, "a.mp3#t=10");
+    }
+
+    #[test]
+    fn stray_mode_keyword_does_not_open_a_mode() {
+        let tokens = lex("#MAEK PARAGRAF #soundz x #OIC");
+        assert_eq!(kinds(&tokens), vec![TokenKind::Maek, TokenKind::Paragraf, TokenKind::Soundz, TokenKind::Text, TokenKind::Oic]);
+        let tokens = lex("#MAEK PARAGRAF #raw x #OIC");
+        assert_eq!(kinds(&tokens), vec![TokenKind::Maek, TokenKind::Paragraf, TokenKind::Raw, TokenKind::Text, TokenKind::Oic]);
+        let tokens = lex("#MAEK PARAGRAF see #code here #OIC");
+        assert_eq!(kinds(&tokens), vec![TokenKind::Maek, TokenKind::Paragraf, TokenKind::Text, TokenKind::Code, TokenKind::Text, TokenKind::Oic]);
+    }
+
+    #[test]
+    fn code_text_is_kept_as_written() {
+        let tokens = lex("#MAEK CODEZ rust\nfn main() { #HAI }\n#OIC #GIMMEH CODE a  #b #MKAY");
+        assert_eq!(
+            kinds(&tokens),
+            vec![TokenKind::Maek, TokenKind::Codez, TokenKind::Text, TokenKind::CodeText, TokenKind::Oic,
+                TokenKind::Gimmeh, TokenKind::Code, TokenKind::CodeText, TokenKind::Mkay]
+        );
+        assert_eq!(tokens[3].text, "fn main() { #HAI }");
+        assert_eq!(tokens[7].text, "a  #b");
+    }
+
+    #[test]
+    fn punctuation_glued_to_a_keyword_is_split_off() {
+        let tokens = lex("(#GIMMEH BOLD hi #MKAY).");
+        assert_eq!(kinds(&tokens), vec![TokenKind::Text, TokenKind::Gimmeh, TokenKind::Bold, TokenKind::Text, TokenKind::Mkay, TokenKind::Text]);
+        assert_eq!(tokens[0].text, "(");
+        assert_eq!(tokens[5].text, ").");
+        assert_eq!(tokens[1].space_before, Spacing::Glued);
+        assert_eq!(tokens[5].space_before, Spacing::Glued);
+    }
+
+    #[test]
+    fn whitespace_before_a_token_is_normalised() {
+        let tokens = lex("a   b\n\n  c");
+        let spacing: Vec<Spacing> = tokens.iter().map(|t| t.space_before).collect();
+        assert_eq!(spacing, vec![Spacing::Glued, Spacing::Space, Spacing::Newline]);
+    }
+
+    #[test]
+    fn backslash_escapes_a_keyword() {
+        let tokens = lex("say \\#HAI");
+        assert_eq!(kinds(&tokens), vec![TokenKind::Text, TokenKind::Text]);
+        assert_eq!(tokens[1].text, "#HAI");
+    }
+
+    #[test]
+    fn other_backslashes_are_kept() {
+        let tokens = lex("match \\d+ or \\n \\\\#HAI \\");
+        let text: Vec<&str> = tokens.iter().map(|t| t.text.as_str()).collect();
+        assert_eq!(text, vec!["match", "\\d+", "or", "\\n", "\\#HAI", "\\"]);
+    }
+
+    #[test]
+    fn raw_text_is_not_checked_for_keywords() {
+        let tokens = lex("#GIMMEH RAW <b>#OIC</b> #rustlang #MKAY");
+        assert_eq!(kinds(&tokens).first(), Some(&TokenKind::Gimmeh));
+        assert_eq!(kinds(&tokens).last(), Some(&TokenKind::Mkay));
+        assert!(tokens[2..tokens.len() - 1].iter().all(|t| t.kind == TokenKind::Text));
+    }
+
+    #[test]
+    fn unquoted_value_can_have_several_words() {
+        let tokens = lex("#I HAZ x #IT IZ really happy #MKAY");
+        assert_eq!(kinds(&tokens)[3..], [TokenKind::It, TokenKind::Iz, TokenKind::Text, TokenKind::Text, TokenKind::Mkay]);
+    }
+
+    #[test]
+    fn code_block_keeps_non_ascii_text() {
+        let tokens = lex("#MAEK CODEZ\néabcdef\n#OIC");
+        assert_eq!(kinds(&tokens), vec![TokenKind::Maek, TokenKind::Codez, TokenKind::CodeText, TokenKind::Oic]);
+        assert_eq!(tokens[2].text, "éabcdef");
+    }
+
+    #[test]
+    fn inline_code_keeps_non_ascii_text() {
+        let tokens = lex("#GIMMEH CODE ééé x #MKAY");
+        assert_eq!(kinds(&tokens), vec![TokenKind::Gimmeh, TokenKind::Code, TokenKind::CodeText, TokenKind::Mkay]);
+        assert_eq!(tokens[2].text, "ééé x");
+    }
+
+    #[test]
+    fn last_word_follows_the_same_hash_rules() {
+        assert_eq!(kinds(&lex("a#b x")), kinds(&lex("x a#b")));
+        assert_eq!(kinds(&lex("#I x")), vec![TokenKind::I, TokenKind::Text]);
+        assert_eq!(kinds(&lex("x #I")), vec![TokenKind::Text, TokenKind::I]);
+        assert!(MarkdownLexicalAnalyzer::new("x #BOGUS").tokenize().is_err());
+    }
+}
@@ -0,0 +1,220 @@
+/*
+Library side of the lolcode markdown compiler.
+The lolcompiler binary is only a thin wrapper around compile_to_html,
+so other tools can embed the compiler and handle errors themselves.
+ */
+use std::fs;
+use std::io;
+use std::path::Path;
+
+pub mod ast;
+pub mod error;
+pub mod lexer;
+pub mod semantic;
+pub mod syntax;
+
+pub use ast::{Block, Document, Inline, List};
+pub use error::{CompileError, Diagnostic, Position};
+pub use lexer::{LexicalAnalyzer, MarkdownLexicalAnalyzer, Spacing, Token, TokenKind};
+pub use semantic::{escape_attribute, escape_comment, escape_text, SemanticAnalyzer};
+pub use syntax::{MarkdownSyntaxAnalyzer, SyntaxAnalyzer};
+
+
+/// Trait for a simple lolcompiler front-end.
+/// Errors are returned to the caller instead of exiting.
+pub trait Compiler {
+/// Begin the compilation process (entry point), returns the generated HTML.
+fn compile(&mut self, source: &str) -> Result<String, CompileError>;
+/// Get the next token from the lexical analyzer.
+fn next_token(&mut self) -> Token;
+/// Run the syntax analyzer starting from <lolcode>, returns the AST.
+fn parse(&mut self) -> Result<Document, CompileError>;
+/// Get the current token being processed.
+fn current_token(&self) -> Token;
+/// Set the current token (typically used internally).
+fn set_current_token(&mut self, tok: Token);
+}
+
+
+//what a successful compile hands back: the page and anything worth warning about in it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CompileOutput {
+    pub html: String,
+    pub warnings: Vec<Diagnostic>,
+}
+
+//runs every phase (lexical, syntax, semantic) over the source and returns the HTML
+pub fn compile_to_html(source: &str) -> Result<String, CompileError> {
+    compile_with_warnings(source).map(|output| output.html)
+}
+
+//same as compile_to_html, but the warnings come back together with the HTML
+pub fn compile_with_warnings(source: &str) -> Result<CompileOutput, CompileError> {
+    run(MarkdownSyntaxAnalyzer::new(), source)
+}
+
+//same as compile_with_warnings, but diagnostics are reported against file_name
+pub fn compile_file_to_html(file_name: &str, source: &str) -> Result<CompileOutput, CompileError> {
+    run(MarkdownSyntaxAnalyzer::with_file_name(file_name), source)
+}
+
+//same as compile_file_to_html, but javascript: and data: addresses are rejected
+pub fn compile_file_to_html_safe(file_name: &str, source: &str) -> Result<CompileOutput, CompileError> {
+    let mut compiler = MarkdownSyntaxAnalyzer::with_file_name(file_name);
+    compiler.safe_mode = true;
+    run(compiler, source)
+}
+
+//compiles and moves the warnings out of the analyzer together with the HTML
+fn run(mut compiler: MarkdownSyntaxAnalyzer, source: &str) -> Result<CompileOutput, CompileError> {
+    let html = compiler.compile(source)?;
+    Ok(CompileOutput { html, warnings: compiler.warnings })
+}
+
+/*
+Writes the HTML next to its final path first and then renames it over the
+target, so a failed or interrupted write never leaves a half written output file.
+Only call this once every phase has succeeded.
+ */
+pub fn write_html(path: &Path, html: &str) -> io::Result<()> {
+    let mut temp_name = path.file_name().unwrap_or_default().to_os_string();
+    temp_name.push(".tmp");
+    let temp_path = path.with_file_name(temp_name);
+
+    if let Err(e) = fs::write(&temp_path, html) {
+        let _ = fs::remove_file(&temp_path);
+        return Err(e);
+    }
+    fs::rename(&temp_path, path).inspect_err(|_| {
+        let _ = fs::remove_file(&temp_path);
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    //the files in tests/ and the exit code the binary gives for each, 0 means HTML was generated
+    const FIXTURES: [(&str, &str, i32); 9] = [
+        ("Test2.lol", include_str!("../tests/Test2.lol"), 0),
+        ("Test3.lol", include_str!("../tests/Test3.lol"), 0),
+        ("Test4.lol", include_str!("../tests/Test4.lol"), 2),
+        ("Test5.lol", include_str!("../tests/Test5.lol"), 3),
+        ("Test6.lol", include_str!("../tests/Test6.lol"), 0),
+        ("Test7.lol", include_str!("../tests/Test7.lol"), 4),
+        ("Test8.lol", include_str!("../tests/Test8.lol"), 0),
+        ("Test9.lol", include_str!("../tests/Test9.lol"), 0),
+        ("Test10.lol", include_str!("../tests/Test10.lol"), 0),
+    ];
+
+    #[test]
+    fn fixtures_compile_or_fail_with_the_right_kind() {
+        for (name, source, expected) in FIXTURES {
+            let code = compile_to_html(source).map_or_else(|e| e.exit_code(), |_| 0);
+            assert_eq!(code, expected, "{}", name);
+        }
+    }
+
+    //the page around a fixture's body, they all share the same layout
+    fn page(comment: &str, title: &str, body: &str) -> String {
+        format!(
+            "<!DOCTYPE html>\n<html>\n<!-- {} -->\n<head>\n<meta charset=\"utf-8\">\n<title>{}</title>\n</head>\n<body>\n{}</body>\n</html>\n",
+            comment, title, body
+        )
+    }
+
+    const LIFE: &str = "<p><i>What is the meaning of life.</i>\nThe meaning of <b>life</b> is 42</p>\n";
+
+    #[test]
+    fn fixtures_compile_to_the_expected_html() {
+        let simple = "This test case assess that your compiler processes a simple lolcode file.";
+        let cases = [
+            ("Test2.lol", include_str!("../tests/Test2.lol"), page(simple, "Test 2", "This is test case 2.\n")),
+            ("Test3.lol", include_str!("../tests/Test3.lol"), page(simple, "Test 3", "This is <b>test case 3</b> .\n")),
+            ("Test6.lol", include_str!("../tests/Test6.lol"), page("This test case assess that your compiler resolves a simple variable.", "Test 6", LIFE)),
+            (
+                "Test8.lol",
+                include_str!("../tests/Test8.lol"),
+                page(
+                    "This test case assess that your compiler resolves variable scoping.",
+                    "Test 8",
+                    &format!("{}<p>No. The meaning of life is really happiness</p>\n", LIFE),
+                ),
+            ),
+            (
+                "Test9.lol",
+                include_str!("../tests/Test9.lol"),
+                page(
+                    "This test case assess that your compiler resolves multiple variable.",
+                    "Test 9",
+                    &format!("{}<p>No. The meaning of life is really happiness</p>\n", LIFE),
+                ),
+            ),
+            (
+                "Test10.lol",
+                include_str!("../tests/Test10.lol"),
+                page(
+                    "This test case assess that uses other legal syntax.",
+                    "Test 10",
+                    "<p><i>What is the meaning of life.</i>\nThe meaning of <b>life</b> is happiness.</p>\nHere is my proof\n<br>\n<iframe src=\"https://tinyurl.com/cosc455dehlinger\"></iframe>\n",
+                ),
+            ),
+        ];
+        for (name, source, expected) in cases {
+            let html = compile_to_html(source).unwrap_or_else(|e| panic!("{} failed: {}", name, e));
+            assert_eq!(html, expected, "{}", name);
+        }
+    }
+
+    #[test]
+    fn documents_are_html5_with_a_body() {
+        let html = compile_to_html(include_str!("../tests/Test2.lol")).unwrap();
+        assert!(html.starts_with("<!DOCTYPE html>\n<html>\n"), "{}", html);
+        assert!(html.contains("<head>\n<meta charset=\"utf-8\">\n<title>"), "{}", html);
+        assert!(html.contains("</head>\n<body>\n") && html.ends_with("</body>\n</html>\n"), "{}", html);
+    }
+
+    #[test]
+    fn escaped_and_raw_keywords_come_out_as_text() {
+        let source = "#HAI #MAEK HEAD #GIMMEH TITLE t #MKAY #OIC a<b \\#HAI #GIMMEH RAW #OIC <i> #rustlang #MKAY #KTHXBYE";
+        let html = compile_to_html(source).unwrap();
+        assert!(html.contains("a&lt;b #HAI #OIC &lt;i&gt; #rustlang"), "{}", html);
+    }
+
+    #[test]
+    fn inline_and_block_code_are_escaped_not_parsed() {
+        let source = "#HAI #MAEK HEAD #GIMMEH TITLE t #MKAY #OIC #MAEK CODEZ rust\nlet x = a < b; #HAI\n#OIC #GIMMEH CODE #MAEK x #MKAY #KTHXBYE";
+        let html = compile_to_html(source).unwrap();
+        assert!(html.contains("<pre><code class=\"language-rust\">let x = a &lt; b; #HAI</code></pre>"), "{}", html);
+        assert!(html.contains("<code>#MAEK x</code>"), "{}", html);
+    }
+
+    #[test]
+    fn quotes_rules_and_preformatted_blocks() {
+        let source = "#HAI #MAEK HEAD #GIMMEH TITLE t #MKAY #OIC
+#MAEK QUOTE to be #GIMMEH CITE me #MKAY #OIC #GIMMEH LINE #MKAY
+#MAEK PREFORMATTD
+  a   b
+    #GIMMEH BOLD c #MKAY
+#OIC #KTHXBYE";
+        let html = compile_to_html(source).unwrap();
+        assert!(html.contains("<blockquote>\n<p>to be</p>\n<footer><cite>me</cite></footer>\n</blockquote>\n<hr>\n"), "{}", html);
+        assert!(html.contains("<pre>  a   b\n    <b>c</b></pre>\n"), "{}", html);
+    }
+
+    #[test]
+    fn video_files_get_a_video_tag_and_pages_an_iframe() {
+        let source = "#HAI #MAEK HEAD #GIMMEH TITLE v #MKAY #OIC #GIMMEH VIDZ clip.mp4 #MKAY #GIMMEH VIDZ https://example.com/watch #MKAY #KTHXBYE";
+        let html = compile_to_html(source).unwrap();
+        assert!(html.contains("<video controls><source src=\"clip.mp4\"></video>"), "{}", html);
+        assert!(html.contains("<iframe src=\"https://example.com/watch\"></iframe>"), "{}", html);
+    }
+
+    #[test]
+    fn warnings_come_back_with_the_html() {
+        let output = compile_with_warnings("#HAI #MAEK HEAD #GIMMEH TITLE a #MKAY #OIC #GIMMEH PIKCHUR cat.png #MKAY #KTHXBYE")
+            .expect("a missing alt text is only a warning");
+        assert!(output.html.contains("<img src=\"cat.png\" alt=\"\">"), "{}", output.html);
+        assert_eq!(output.warnings.len(), 1);
+    }
+}
@@ -0,0 +1,456 @@
+use crate::ast::{words_text, Block, CodeBlock, Comment, Document, Head, Heading, Inline, List, Quote, Table, TableRow, Paragraph, VariableDefine, VariableUse};
+use crate::error::{CompileError, Diagnostic};
+use crate::lexer::{Spacing, Token};
+
+/*
+Semantic analyzer: walks the AST from the syntax analyzer, checks static variable usage
+and converts LOLCODE markdown tags to HTML
+
+ */
+pub struct SemanticAnalyzer {
+    pub file_name: String,
+
+    /* these are parallel for variable definitions, the indexes map with each other
+      initially, i tried to use a hashmap since that was what gpt suggested but got confused
+    */
+    pub variable_names: Vec<String>,
+    pub variable_values: Vec<String>,
+    //index into the vectors above where each open scope starts, innermost last
+    //the global scope starts at 0 and is never popped
+    pub scope_starts: Vec<usize>,
+    //--safe: reject javascript: and data: addresses instead of emitting them
+    pub safe_mode: bool,
+    //ids already given to headings, so a repeated heading gets a numbered id
+    pub heading_ids: Vec<String>,
+    //problems that don't stop compilation, like an image without alt text
+    pub warnings: Vec<Diagnostic>,
+}
+
+
+impl SemanticAnalyzer {
+    pub fn new(file_name: &str) -> Self {
+        Self {
+            file_name: file_name.to_string(),
+            variable_names:Vec::new(),
+            variable_values:Vec::new(),
+            scope_starts: vec![0],
+            safe_mode: false,
+            heading_ids: Vec::new(),
+            warnings: Vec::new(),
+        }
+    }
+
+    //convert the AST to HTML, checking variables on the way
+     pub fn convert_html(&mut self, document: &Document) -> Result<String, CompileError> {
+        //for the generated html
+        let mut html = String::new();
+
+        //valid file then open an HTML5 document
+        html.push_str("<!DOCTYPE html>\n<html>\n");
+
+        for comment in &document.comments {
+            self.comment_html(comment, &mut html);
+        }
+        self.head_html(&document.head, &mut html);
+        //everything after the head is page content
+        html.push_str("<body>\n");
+        for block in &document.body {
+            //every block starts on its own line, text runs keep their own line breaks
+            if !matches!(block, Block::Inline(_)) && !html.ends_with('\n') {
+                html.push('\n');
+            }
+            self.block_html(block, &mut html)?;
+        }
+
+        //</body></html> pushed at end here
+        if !html.ends_with('\n') {
+            html.push('\n');
+        }
+        html.push_str("</body>\n</html>\n");
+        Ok(html)
+    }
+
+    //<!-- words -->
+    fn comment_html(&self, comment: &Comment, html: &mut String) {
+        html.push_str("<!-- ");
+        html.push_str(&escape_comment(&words_text(&comment.words)));
+        html.push_str(" -->\n");
+    }
+
+    //<head><meta charset><title>words</title></head>
+    fn head_html(&self, head: &Head, html: &mut String) {
+        html.push_str("<head>\n<meta charset=\"utf-8\">\n<title>");
+        html.push_str(&escape_text(&words_text(&head.title)));
+        html.push_str("</title>\n</head>\n");
+    }
+
+    fn block_html(&mut self, block: &Block, html: &mut String) -> Result<(), CompileError> {
+        match block {
+            Block::Paragraph(paragraph) => self.paragraph_html(paragraph, html)?,
+            Block::List(list) => self.list_html(list, html)?,
+            Block::Heading(heading) => self.heading_html(heading, html)?,
+            Block::Table(table) => self.table_html(table, html)?,
+            Block::Code(code) => self.code_html(code, html),
+            Block::Quote(quote) => self.quote_html(quote, html)?,
+            Block::Line => html.push_str("<hr>\n"),
+            Block::Preformatted(content) => {
+                html.push_str("<pre>");
+                content.iter().try_for_each(|inline| self.inline_html(inline, html))?;
+                html.push_str("</pre>\n");
+            }
+            Block::Comment(comment) => self.comment_html(comment, html),
+            Block::VariableDefine(define) => self.define_variable(define),
+            Block::Inline(inline) => self.inline_html(inline, html)?,
+        }
+        Ok(())
+    }
+
+    //#MAEK PARAGRAF opens a scope and #OIC closes it, so a paragraph's
+    //definitions only shadow the outer ones inside that paragraph
+    fn paragraph_html(&mut self, paragraph: &Paragraph, html: &mut String) -> Result<(), CompileError> {
+        self.enter_scope();
+        if let Some(define) = &paragraph.define {
+            self.define_variable(define);
+        }
+        html.push_str("<p>");
+        let result = paragraph.content.iter().try_for_each(|inline| self.inline_html(inline, html));
+        html.push_str("</p>\n");
+        self.exit_scope();
+        result
+    }
+
+    //<hN id="slug">, N has to be 1 to 6
+    fn heading_html(&mut self, heading: &Heading, html: &mut String) -> Result<(), CompileError> {
+        let level = match heading.level.text.parse::<u8>() {
+            Ok(level @ 1..=6) => level,
+            _ => {
+                let message = format!("heading level '{}' must be a number from 1 to 6", heading.level.text);
+                return Err(CompileError::Semantic(Diagnostic::new(&self.file_name, heading.level.position(), &heading.level.text, message)));
+            }
+        };
+
+        let mut text = String::new();
+        self.plain_text(&heading.content, &mut text)?;
+        let id = self.unique_id(slugify(&text));
+
+        html.push_str(&format!("<h{} id=\"{}\">", level, escape_attribute(&id)));
+        for inline in &heading.content {
+            self.inline_html(inline, html)?;
+        }
+        html.push_str(&format!("</h{}>\n", level));
+        Ok(())
+    }
+
+    //the text of some inline content without any markup, for heading ids
+    fn plain_text(&self, content: &[Inline], text: &mut String) -> Result<(), CompileError> {
+        for inline in content {
+            match inline {
+                Inline::Text(word) => text.push_str(&word.text),
+                Inline::Space(_) => text.push(' '),
+                Inline::Bold(inner) | Inline::Italics(inner) => self.plain_text(inner, text)?,
+                Inline::Link { content, .. } => self.plain_text(content, text)?,
+                Inline::VariableUse(variable) => text.push_str(&self.lookup_variable(variable)?),
+                Inline::Raw(words) => text.push_str(&words_text(words)),
+                Inline::Code(code) => text.push_str(&code.text),
+                Inline::Image { alt, .. } => text.push_str(&words_text(alt)),
+                Inline::Newline | Inline::Audio(_) | Inline::Video(_) => {}
+            }
+        }
+        Ok(())
+    }
+
+    //first heading with a slug gets it as is, the next ones get -1, -2, ...
+    fn unique_id(&mut self, slug: String) -> String {
+        let mut id = slug.clone();
+        let mut n = 1;
+        while self.heading_ids.contains(&id) {
+            id = format!("{}-{}", slug, n);
+            n += 1;
+        }
+        self.heading_ids.push(id.clone());
+        id
+    }
+
+    //<pre><code>, the language goes in a class the way highlighters expect it
+    fn code_html(&self, code: &CodeBlock, html: &mut String) {
+        html.push_str("<pre><code");
+        if let Some(language) = &code.language {
+            html.push_str(" class=\"language-");
+            html.push_str(&escape_attribute(&language.text));
+            html.push('"');
+        }
+        html.push('>');
+        html.push_str(&escape_text(&code.code.text));
+        html.push_str("</code></pre>\n");
+    }
+
+    //<blockquote>, the CITE goes in a <footer> under the quoted text
+    fn quote_html(&mut self, quote: &Quote, html: &mut String) -> Result<(), CompileError> {
+        html.push_str("<blockquote>\n<p>");
+        quote.content.iter().try_for_each(|inline| self.inline_html(inline, html))?;
+        html.push_str("</p>\n");
+        if let Some(cite) = &quote.cite {
+            html.push_str("<footer><cite>");
+            cite.iter().try_for_each(|inline| self.inline_html(inline, html))?;
+            html.push_str("</cite></footer>\n");
+        }
+        html.push_str("</blockquote>\n");
+        Ok(())
+    }
+
+    /*
+    <table> with the HEDR row in <thead> and the rest in <tbody>.
+    Every row needs as many cells as the first one (the header if there is one).
+     */
+    fn table_html(&mut self, table: &Table, html: &mut String) -> Result<(), CompileError> {
+        let mut rows = table.header.iter().chain(&table.rows);
+        if let Some(first) = rows.next() {
+            let expected = first.cells.len();
+            for row in rows {
+                if row.cells.len() != expected {
+                    let message = format!("table row has {} cells but the first row has {}", row.cells.len(), expected);
+                    return Err(CompileError::Semantic(Diagnostic::new(&self.file_name, row.keyword.position(), &row.keyword.text, message)));
+                }
+            }
+        }
+
+        html.push_str("<table>\n");
+        if let Some(header) = &table.header {
+            html.push_str("<thead>\n");
+            self.row_html(header, "th", html)?;
+            html.push_str("</thead>\n");
+        }
+        if !table.rows.is_empty() {
+            html.push_str("<tbody>\n");
+            for row in &table.rows {
+                self.row_html(row, "td", html)?;
+            }
+            html.push_str("</tbody>\n");
+        }
+        html.push_str("</table>\n");
+        Ok(())
+    }
+
+    //<tr> with every cell in a th or td
+    fn row_html(&mut self, row: &TableRow, cell_tag: &str, html: &mut String) -> Result<(), CompileError> {
+        html.push_str("<tr>");
+        for cell in &row.cells {
+            html.push_str(&format!("<{}>", cell_tag));
+            for inline in cell {
+                self.inline_html(inline, html)?;
+            }
+            html.push_str(&format!("</{}>", cell_tag));
+        }
+        html.push_str("</tr>\n");
+        Ok(())
+    }
+
+    //<ul> or <ol>, a nested list goes inside the <li> it belongs to
+    fn list_html(&mut self, list: &List, html: &mut String) -> Result<(), CompileError> {
+        let tag = if list.ordered { "ol" } else { "ul" };
+        html.push_str(&format!("<{}>", tag));
+        for item in &list.items {
+            html.push_str("<li>");
+            for inline in &item.content {
+                self.inline_html(inline, html)?;
+            }
+            if let Some(sublist) = &item.sublist {
+                html.push('\n');
+                self.list_html(sublist, html)?;
+            }
+            html.push_str("</li>\n");
+        }
+        html.push_str(&format!("</{}>\n", tag));
+        Ok(())
+    }
+
+    fn inline_html(&mut self, inline: &Inline, html: &mut String) -> Result<(), CompileError> {
+        match inline {
+            // Plain text
+            Inline::Text(word) => html.push_str(&escape_text(&word.text)),
+            //whitespace from the source, normalised by the lexer
+            Inline::Space(Spacing::Glued) => {}
+            Inline::Space(Spacing::Space) => html.push(' '),
+            Inline::Space(Spacing::Newline) => html.push('\n'),
+            Inline::Bold(content) => {
+                html.push_str("<b>");
+                for inner in content {
+                    self.inline_html(inner, html)?;
+                }
+                html.push_str("</b>");
+            }
+            Inline::Italics(content) => {
+                html.push_str("<i>");
+                for inner in content {
+                    self.inline_html(inner, html)?;
+                }
+                html.push_str("</i>");
+            }
+            Inline::VariableUse(variable) => {
+                let value = self.lookup_variable(variable)?;
+                html.push_str(&escape_text(&value));
+            }
+            Inline::Newline => html.push_str("<br>"),
+            //same as plain text, keywords inside were never interpreted
+            Inline::Raw(words) => html.push_str(&escape_text(&words_text(words))),
+            Inline::Code(code) => {
+                html.push_str("<code>");
+                html.push_str(&escape_text(&code.text));
+                html.push_str("</code>");
+            }
+            Inline::Audio(address) => {
+                self.check_address(address)?;
+                html.push_str("<audio controls><source src=\"");
+                html.push_str(&escape_attribute(&address.text));
+                html.push_str("\"></audio>");
+            }
+            //<a href>, with the address as the text if none was given
+            Inline::Link { address, content } => {
+                self.check_address(address)?;
+                html.push_str("<a href=\"");
+                html.push_str(&escape_attribute(&address.text));
+                html.push_str("\">");
+                if content.is_empty() {
+                    html.push_str(&escape_text(&address.text));
+                }
+                for inner in content {
+                    self.inline_html(inner, html)?;
+                }
+                html.push_str("</a>");
+            }
+            //<img src alt>, an empty alt is still written but gets a warning
+            Inline::Image { address, alt } => {
+                self.check_address(address)?;
+                if alt.is_empty() {
+                    self.warnings.push(Diagnostic::new(&self.file_name, address.position(), &address.text, format!("image '{}' has no alt text", address.text)));
+                }
+                html.push_str("<img src=\"");
+                html.push_str(&escape_attribute(&address.text));
+                html.push_str("\" alt=\"");
+                html.push_str(&escape_attribute(&words_text(alt)));
+                html.push_str("\">");
+            }
+            //a file the browser can play itself gets a <video>, anything else is a page to embed
+            Inline::Video(address) => {
+                self.check_address(address)?;
+                if is_video_file(&address.text) {
+                    html.push_str("<video controls><source src=\"");
+                    html.push_str(&escape_attribute(&address.text));
+                    html.push_str("\"></video>");
+                } else {
+                    html.push_str("<iframe src=\"");
+                    html.push_str(&escape_attribute(&address.text));
+                    html.push_str("\"></iframe>");
+                }
+            }
+        }
+        Ok(())
+    }
+
+    //in --safe mode, addresses that would run script or inline content are errors
+    fn check_address(&self, address: &Token) -> Result<(), CompileError> {
+        if !self.safe_mode {
+            return Ok(());
+        }
+        //browsers ignore whitespace and control chars in the scheme, so do the same before comparing
+        let scheme: String = address.text.chars().filter(|c| !c.is_whitespace() && !c.is_control()).collect::<String>().to_ascii_lowercase();
+        if scheme.starts_with("javascript:") || scheme.starts_with("data:") {
+            return Err(CompileError::Semantic(Diagnostic::new(&self.file_name, address.position(), &address.text, format!("unsafe address '{}' is not allowed in --safe mode", address.text))));
+        }
+        Ok(())
+    }
+
+    fn enter_scope(&mut self) {
+        self.scope_starts.push(self.variable_names.len());
+    }
+
+    //drops every definition made since the matching enter_scope
+    fn exit_scope(&mut self) {
+        if self.scope_starts.len() > 1 && let Some(start) = self.scope_starts.pop() {
+            self.variable_names.truncate(start);
+            self.variable_values.truncate(start);
+        }
+    }
+
+    //append definition to the innermost scope
+    fn define_variable(&mut self, define: &VariableDefine) {
+        self.variable_names.push(define.name.text.clone());
+        self.variable_values.push(define.value_text());
+    }
+
+    //Search backwards, innermost scope first, to support shadowing semantics (nearest definition wins).
+    //this small chunk is also heavily assisted with gpt:
+    fn lookup_variable(&self, variable: &VariableUse) -> Result<String, CompileError> {
+        let name = &variable.name.text;
+        for idx in (0..self.variable_names.len()).rev() {
+            if self.variable_names[idx].eq_ignore_ascii_case(name) {
+                return Ok(self.variable_values[idx].clone());
+            }
+        }
+        // not found, static semantci error
+        Err(CompileError::Semantic(Diagnostic::new(&self.file_name, variable.name.position(), name, format!("variable '{}' not defined.", name))))
+    }
+}
+
+//does the address point straight at a video file (by extension, ignoring ?query and #fragment)
+fn is_video_file(address: &str) -> bool {
+    let path = address.split(['?', '#']).next().unwrap_or_default();
+    let file = path.rsplit('/').next().unwrap_or_default();
+    match file.rsplit_once('.') {
+        Some((_, extension)) => ["mp4", "webm", "ogv", "ogg", "mov", "m4v"].iter().any(|e| e.eq_ignore_ascii_case(extension)),
+        None => false,
+    }
+}
+
+//lowercase letters and digits, everything else becomes a single '-'
+fn slugify(text: &str) -> String {
+    let mut slug = String::new();
+    for c in text.chars() {
+        if c.is_alphanumeric() {
+            slug.extend(c.to_lowercase());
+        } else if !slug.is_empty() && !slug.ends_with('-') {
+            slug.push('-');
+        }
+    }
+    let slug = slug.trim_end_matches('-');
+    if slug.is_empty() { "section".to_string() } else { slug.to_string() }
+}
+
+//text between tags: only &, < and > mean something there
+pub fn escape_text(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+//attribute values are always written in double quotes, so quotes need escaping too
+pub fn escape_attribute(text: &str) -> String {
+    let mut escaped = String::with_capacity(text.len());
+    for c in text.chars() {
+        match c {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            _ => escaped.push(c),
+        }
+    }
+    escaped
+}
+
+//entities aren't decoded inside <!-- -->, so break up every "--" instead (that also covers "-->")
+pub fn escape_comment(text: &str) -> String {
+    let mut escaped = text.to_string();
+    while escaped.contains("--") {
+        escaped = escaped.replace("--", "- -");
+    }
+    escaped
+}
@@ -0,0 +1,1299 @@
+use crate::ast::{Block, CodeBlock, Comment, Document, Head, Heading, Inline, List, ListItem, Paragraph, Quote, Table, TableRow, VariableDefine, VariableUse};
+use crate::error::{CompileError, Diagnostic};
+use crate::lexer::{MarkdownLexicalAnalyzer, Spacing, Token, TokenKind};
+use crate::semantic::SemanticAnalyzer;
+use crate::Compiler;
+
+/// OPTION 1 - Trait for a recursive descent Syntax Analyzer
+/// over the lexer's tokens. Each function parses a nonterminal in
+/// the grammar and returns its AST node. On error: return the CompileError, the caller
+/// records it and resynchronizes at the next #MKAY, #OIC or #MAEK.
+pub trait SyntaxAnalyzer {
+    fn parse_lolcode(&mut self) -> Result<Document, CompileError>;
+    fn parse_head(&mut self) -> Result<Head, CompileError>;
+    fn parse_title(&mut self) -> Result<Vec<Token>, CompileError>;
+    fn parse_comment(&mut self) -> Result<Comment, CompileError>;
+    fn parse_body(&mut self) -> Result<Vec<Block>, CompileError>;
+    fn parse_paragraph(&mut self) -> Result<Block, CompileError>;
+    fn parse_inner_paragraph(&mut self) -> Result<Vec<Inline>, CompileError>;
+    fn parse_inline(&mut self) -> Result<Inline, CompileError>;
+    fn parse_variable_define(&mut self) -> Result<Option<VariableDefine>, CompileError>;
+    fn parse_variable_use(&mut self) -> Result<Inline, CompileError>;
+    fn parse_bold(&mut self) -> Result<Inline, CompileError>;
+    fn parse_italics(&mut self) -> Result<Inline, CompileError>;
+    fn parse_heading(&mut self) -> Result<Heading, CompileError>;
+    fn parse_code_block(&mut self) -> Result<CodeBlock, CompileError>;
+    fn parse_code(&mut self) -> Result<Inline, CompileError>;
+    fn parse_quote(&mut self) -> Result<Quote, CompileError>;
+    fn parse_line(&mut self) -> Result<Block, CompileError>;
+    fn parse_preformatted(&mut self) -> Result<Block, CompileError>;
+    fn parse_table(&mut self) -> Result<Table, CompileError>;
+    fn parse_table_row(&mut self) -> Result<TableRow, CompileError>;
+    fn parse_table_cell(&mut self) -> Result<Vec<Inline>, CompileError>;
+    fn parse_list(&mut self) -> Result<List, CompileError>;
+    fn parse_list_items(&mut self) -> Result<Vec<ListItem>, CompileError>;
+    fn parse_list_item(&mut self) -> Result<ListItem, CompileError>;
+    fn parse_inner_list(&mut self) -> Result<Vec<Inline>, CompileError>;
+    fn parse_audio(&mut self) -> Result<Inline, CompileError>;
+    fn parse_video(&mut self) -> Result<Inline, CompileError>;
+    fn parse_link(&mut self) -> Result<Inline, CompileError>;
+    fn parse_image(&mut self) -> Result<Inline, CompileError>;
+    fn parse_newline(&mut self) -> Result<Inline, CompileError>;
+    fn parse_raw(&mut self) -> Result<Inline, CompileError>;
+    fn parse_text(&mut self) -> Result<Inline, CompileError>;
+}
+
+/*
+
+Defining the struct for the syntax analyzer.
+Takes in lexer, current_token, and defined_variable
+
+ */
+pub struct MarkdownSyntaxAnalyzer {
+    pub lexer: MarkdownLexicalAnalyzer,
+    //current token, with its span for diagnostics
+    current_token: Token,
+    //name of the file being compiled, passed on to the lexer
+    file_name: String,
+    //variables defined so far, checked by parse_variable_use
+    defined_variables: Vec<String>,
+    //index into defined_variables where each open paragraph scope starts, innermost last
+    scope_starts: Vec<usize>,
+    //errors collected so far, parsing keeps going after each one (panic-mode recovery)
+    diagnostics: Vec<CompileError>,
+    //inside #MAEK PREFORMATTD, whitespace between inline items is kept as written
+    preformatted: bool,
+    //inside the text of a link, where another link isn't allowed (<a> can't nest)
+    in_link: bool,
+    //passed on to the SemanticAnalyzer, see --safe
+    pub safe_mode: bool,
+    //warnings from the last compile, the HTML was still generated
+    pub warnings: Vec<Diagnostic>,
+}
+
+impl Default for MarkdownSyntaxAnalyzer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/*
+    Methods for token recognition and navigation
+    to be used by recusirve descent parser 
+ */
+impl MarkdownSyntaxAnalyzer {
+
+    //constructor
+    pub fn new() -> Self {
+        Self {
+            lexer: MarkdownLexicalAnalyzer::new(""),
+            current_token: Token::default(),
+            file_name: "<input>".into(),
+            defined_variables: Vec::new(),
+            scope_starts: Vec::new(),
+            diagnostics: Vec::new(),
+            preformatted: false,
+            in_link: false,
+            safe_mode: false,
+            warnings: Vec::new(),
+        }
+    }
+
+    //same as new, but diagnostics will name the given file
+    pub fn with_file_name(file_name: &str) -> Self {
+        Self {
+            file_name: file_name.to_string(),
+            ..Self::new()
+        }
+    }
+
+    //builds a syntax error located at the current token
+    fn syntax_error(&self, message: impl Into<String>) -> CompileError {
+        CompileError::Syntax(Diagnostic::new(&self.lexer.file_name, self.current_token.position(), &self.current_token.text, message))
+    }
+
+    //pops next token from lexer’s token stack and updates current_token.
+    //again, similar to the implementation of next_token in assignment 5, but i decided
+    //move functionality into the parser instead of in the compiler
+    //the lexer already rejected unknown lexemes and classified the rest, so nothing can fail here
+    fn next_token(&mut self) -> Token {
+        //end of input is an Eof token located at the end of the source
+        let candidate = self.lexer.tokens.pop().unwrap_or_else(|| self.lexer.eof_token());
+        self.current_token = candidate.clone();
+        candidate
+    }
+
+    //moves to the next token and hands back the one we were on (for leaf AST nodes)
+    fn next_token_taken(&mut self) -> Token {
+        let next = self.lexer.tokens.pop().unwrap_or_else(|| self.lexer.eof_token());
+        std::mem::replace(&mut self.current_token, next)
+    }
+
+    /*
+    Static check for #LEMME SEE: the name must already be defined in this scope or an outer one.
+    If it isn't, look through the tokens we haven't parsed yet to tell a forward
+    reference apart from a variable that is never defined. Only a later definition
+    in this same paragraph or at the global level counts, one in another paragraph
+    would never be in scope here.
+     */
+    fn check_variable_defined(&self, name: &Token) -> Result<(), CompileError> {
+        if self.defined_variables.iter().any(|v| v.eq_ignore_ascii_case(&name.text)) {
+            return Ok(());
+        }
+
+        //remaining tokens are a stack, so walk them from the back to go left to right
+        let rest: Vec<&Token> = self.lexer.tokens.iter().rev().collect();
+
+        //block depth of each remaining token relative to here (#MAEK opens, #OIC closes)
+        let mut depth = 0i32;
+        let depths: Vec<i32> = rest
+            .iter()
+            .map(|t| {
+                match t.kind {
+                    TokenKind::Maek => depth += 1,
+                    TokenKind::Oic => depth -= 1,
+                    _ => {}
+                }
+                depth
+            })
+            .collect();
+        //everything left ends at the global level, so that's how deep we are right now
+        let here = -depth;
+
+        let mut lowest = 0;
+        let later = rest.windows(3).zip(depths).find(|(w, d)| {
+            lowest = lowest.min(*d);
+            let in_scope = here + d == 0 || (*d == 0 && lowest == 0);
+            in_scope && w[0].kind == TokenKind::I && w[1].kind == TokenKind::Haz && w[2].text.eq_ignore_ascii_case(&name.text)
+        });
+        let later = later.map(|(w, _)| w);
+
+        let message = match later {
+            Some(w) => format!("variable '{}' used before definition (defined at line {}, column {})", name.text, w[2].line, w[2].column),
+            None => format!("variable '{}' is never defined", name.text),
+        };
+        Err(CompileError::Semantic(Diagnostic::new(&self.lexer.file_name, name.position(), &name.text, message)))
+    }
+
+    /*
+    Panic-mode recovery: records the error, then skips ahead to the next #MKAY, #OIC or #MAEK
+    (or the end of the program) so parsing can resume there. The caller decides whether
+    to consume the token we stopped at. start is where the failed construct began, if
+    nothing was consumed since then one token is skipped so we never loop on the same error.
+     */
+    fn recover(&mut self, error: CompileError, start: usize) {
+        self.diagnostics.push(error);
+        if self.current_token.span.start == start && !self.at(TokenKind::Eof) {
+            self.next_token();
+        }
+        while !matches!(self.current_token.kind, TokenKind::Mkay | TokenKind::Oic | TokenKind::Maek | TokenKind::Kthxbye | TokenKind::Eof) {
+            self.next_token();
+        }
+    }
+
+    //the ADDRESS right after SOUNDZ, VIDZ, LINKZ or PIKCHUR, the lexer took it verbatim
+    fn parse_address(&mut self, after: &str) -> Result<Token, CompileError> {
+        if !self.at(TokenKind::Address) {
+            return Err(self.syntax_error(format!("expected ADDRESS after {}, got '{}' instead", after, self.current_token.text)));
+        }
+        Ok(self.next_token_taken())
+    }
+
+    //inline content of bold/italics and list items, up to (not including) the #MKAY that closes them
+    //a nested annotation eats its own #MKAY, so the first one left over is ours
+    fn parse_nested_inlines(&mut self) -> Result<Vec<Inline>, CompileError> {
+        let mut content = Vec::new();
+        while !matches!(self.current_token.kind, TokenKind::Mkay | TokenKind::Oic | TokenKind::Maek | TokenKind::Kthxbye | TokenKind::Eof) {
+            let separator = self.separator();
+            let inline = self.parse_inline()?;
+            push_inline(&mut content, separator, inline);
+        }
+        Ok(content)
+    }
+
+    //what goes between the previous inline item and the one starting at the current token
+    fn separator(&self) -> Option<Inline> {
+        if self.preformatted {
+            let whitespace = &self.current_token.whitespace_before;
+            if whitespace.is_empty() {
+                return None;
+            }
+            let mut token = self.current_token.clone();
+            token.kind = TokenKind::Text;
+            token.text = whitespace.clone();
+            token.span = token.span.start - whitespace.len()..token.span.start;
+            return Some(Inline::Text(token));
+        }
+        match self.current_token.space_before {
+            Spacing::Glued => None,
+            spacing => Some(Inline::Space(spacing)),
+        }
+    }
+
+    //is the current token of the given kind
+    #[inline] fn at(&self, kind: TokenKind) -> bool { self.current_token.kind == kind }
+
+    //kind and text of the token after the current one, without removing it
+    fn peek(&self) -> (TokenKind, String) {
+        match self.lexer.tokens.last() {
+            Some(t) => (t.kind, t.text.to_uppercase()),
+            None => (TokenKind::Eof, String::new()),
+        }
+    }
+   }
+
+
+/*
+    Implements the SyntaxAnalyzer trait for MarkdownSyntaxAnalyzer
+   Each func is for a nonterminal in the grammar and builds its AST node
+ */
+impl SyntaxAnalyzer for MarkdownSyntaxAnalyzer {
+
+    //<lolcode> ::= HAI <comments> <head> <body> KTHXBYE
+    //every error found on the way is reported together at the end
+    fn parse_lolcode(&mut self) -> Result<Document, CompileError> {
+        if !self.at(TokenKind::Hai) {
+            return Err(self.syntax_error("Program MUST start with #HAI"));
+        }
+        self.next_token();
+
+        let mut document = Document::default();
+        //optional comment block after HAI
+        if self.at(TokenKind::Obtw) {
+            let start = self.current_token.span.start;
+            match self.parse_comment() {
+                Ok(comment) => document.comments.push(comment),
+                Err(e) => self.recover(e, start),
+            }
+        }
+
+        //appropriate heady and body sections
+        let start = self.current_token.span.start;
+        match self.parse_head() {
+            Ok(head) => document.head = head,
+            Err(e) => {
+                self.recover(e, start);
+                //the #OIC most likely closes the broken head
+                if self.at(TokenKind::Oic) {
+                    self.next_token();
+                }
+            }
+        }
+        document.body = self.parse_body()?;
+
+        //ends with KTHXBYE
+        if self.at(TokenKind::Kthxbye) {
+            self.next_token();
+        } else {
+            self.diagnostics.push(self.syntax_error("Program MUST end with #KTHXBYE"));
+        }
+
+        match CompileError::from_errors(std::mem::take(&mut self.diagnostics)) {
+            Some(error) => Err(error),
+            None => Ok(document),
+        }
+    }
+
+    //<head> ::= MAEK HEAD <title> OIC
+    fn parse_head(&mut self) -> Result<Head, CompileError> {
+        if !self.at(TokenKind::Maek) {
+            return Err(self.syntax_error("A head annotation start with #MAEK"));
+        }
+        self.next_token();
+
+        if !self.at(TokenKind::Head) {
+            return Err(self.syntax_error("A head annotation must have HEAD after #MAEK"));
+        }
+        self.next_token();
+
+        //parse title inside head
+        let title = self.parse_title()?;
+        //checks for #OIC at end
+        if !self.at(TokenKind::Oic) {
+            return Err(self.syntax_error("A head annotation must end with #OIC"));
+        }
+        self.next_token();
+        Ok(Head { title })
+    }
+
+    //<title> ::= GIMMEH TITLE TEXT MKAY 
+    fn parse_title(&mut self) -> Result<Vec<Token>, CompileError> {
+
+       if !self.at(TokenKind::Gimmeh) {
+            return Err(self.syntax_error("title annotation must start with #GIMMEH"));
+        }
+
+    
+        // skip #GIMMEH
+        self.next_token();
+
+        if !self.at(TokenKind::Title) {
+            return Err(self.syntax_error(format!("expected TITLE after #GIMMEH, found '{}'", self.current_token.text)));
+        }
+
+        // skip TITLE
+        self.next_token();
+
+        // accept all tokens until #MKAY
+        let mut words = Vec::new();
+        while !self.at(TokenKind::Mkay) {
+            if self.at(TokenKind::Eof) {
+                return Err(self.syntax_error("missing #MKAY at end of TITLE"));
+            }
+            words.push(self.next_token_taken());
+        }
+
+        // skip #MKAY
+        self.next_token();
+        Ok(words)
+    }
+
+    
+    //<comments> ::= <comment> <comment> | ε
+    //<comment> ::= OBTW TEXT TLDR
+    fn parse_comment(&mut self) -> Result<Comment, CompileError> {
+
+    //comment has to start with OBTW
+    if !self.at(TokenKind::Obtw) {
+        return Err(self.syntax_error("comment must begin with #OBTW"));
+    }
+
+    // move past #OBTW
+    self.next_token();
+
+    // keep consuming tokens until #TLDR
+    let mut words = Vec::new();
+    while !self.at(TokenKind::Tldr) {
+        if self.at(TokenKind::Eof) {
+            return Err(self.syntax_error("comment missing #TLDR terminator"));
+        }
+        words.push(self.next_token_taken());
+    }
+
+    // move past #TLDR
+    self.next_token();
+    Ok(Comment { words })
+    }
+
+
+    //<body> ::= <inner_body> <body>
+    //<inner-body> ::= <paragraph> | <bold> | <italics> | <list> | <audio> | <video> | <newline> 
+    //| <variable-define> | <variable-use> | TEXT | <comment>
+    fn parse_body(&mut self) -> Result<Vec<Block>, CompileError> {
+        let mut blocks = Vec::new();
+
+        // stop if we reach end of program
+        while !self.at(TokenKind::Kthxbye) && !self.at(TokenKind::Eof) {
+            let start = self.current_token.span.start;
+            let spacing = self.current_token.space_before;
+            let block = match self.current_token.kind {
+                //blocks opened by #MAEK, pick paragraph or list from the next token
+                TokenKind::Maek => match self.peek().0 {
+                    TokenKind::List | TokenKind::Numbrd => self.parse_list().map(Block::List),
+                    TokenKind::Tabl => self.parse_table().map(Block::Table),
+                    TokenKind::Codez => self.parse_code_block().map(Block::Code),
+                    TokenKind::Quote => self.parse_quote().map(Block::Quote),
+                    TokenKind::Preformattd => self.parse_preformatted(),
+                    _ => self.parse_paragraph(),
+                },
+                TokenKind::Paragraf => self.parse_paragraph(),
+
+                // variable definitions
+                TokenKind::I => match self.parse_variable_define() {
+                    Ok(Some(define)) => Ok(Block::VariableDefine(define)),
+                    Ok(None) => continue,
+                    Err(e) => Err(e),
+                },
+                // comments
+                TokenKind::Obtw => self.parse_comment().map(Block::Comment),
+
+                //headings only go directly in the body
+                TokenKind::Gimmeh if self.peek().0 == TokenKind::Headin => self.parse_heading().map(Block::Heading),
+                TokenKind::Gimmeh if self.peek().0 == TokenKind::Line => self.parse_line(),
+
+                //inline annotations, variable use and plain text
+                TokenKind::Gimmeh | TokenKind::Lemme | TokenKind::Text => self.parse_inline().map(Block::Inline),
+
+                // unknown token
+                _ => Err(self.syntax_error(format!("unknown '{}' token inside body", self.current_token.text))),
+            };
+            match block {
+                //text right after other text keeps the whitespace between them
+                Ok(Block::Inline(inline)) => {
+                    if matches!(blocks.last(), Some(Block::Inline(_))) && spacing != Spacing::Glued {
+                        blocks.push(Block::Inline(Inline::Space(spacing)));
+                    }
+                    blocks.push(Block::Inline(inline));
+                }
+                Ok(block) => blocks.push(block),
+                Err(e) => {
+                    self.recover(e, start);
+                    //a #MKAY or #OIC here closes whatever was broken, a #MAEK starts the next block
+                    if self.at(TokenKind::Mkay) || self.at(TokenKind::Oic) {
+                        self.next_token();
+                    }
+                }
+            }
+        }
+        Ok(blocks)
+    }
+
+    //<paragraph> ::= MAEK PARAGRAF <variable-define> <inner-paragraph> OIC
+    fn parse_paragraph(&mut self) -> Result<Block, CompileError> {
+        if !self.at(TokenKind::Maek) {
+            return Err(self.syntax_error("A paragraph annotation should start with '#MAEK"));
+        }
+        self.next_token();
+
+        if !self.at(TokenKind::Paragraf) {
+            return Err(self.syntax_error(format!("Found '{}' after #MAEK, should be #PARAGRAF", self.current_token.text)));
+        }
+        self.next_token();
+
+        //a paragraph is its own scope for variables, same as in the SemanticAnalyzer
+        self.scope_starts.push(self.defined_variables.len());
+
+        //optional var def at paragraph start
+        let start = self.current_token.span.start;
+        let define = match self.parse_variable_define() {
+            Ok(define) => define,
+            Err(e) => {
+                self.recover(e, start);
+                if self.at(TokenKind::Mkay) {
+                    self.next_token();
+                }
+                None
+            }
+        };
+        //parse text/inline annotations, errors inside are recovered there
+        let content = self.parse_inner_paragraph()?;
+
+        //close the scope before anything can fail
+        if let Some(start) = self.scope_starts.pop() {
+            self.defined_variables.truncate(start);
+        }
+
+        if !self.at(TokenKind::Oic) {
+            return Err(self.syntax_error("A head paragraph must end with #OIC"));
+        }
+        self.next_token();
+        Ok(Block::Paragraph(Paragraph { define, content }))
+    }
+
+
+    //<inner-paragraph> ::= <inner-text> <inner-paragraph> | ε
+    fn parse_inner_paragraph(&mut self) -> Result<Vec<Inline>, CompileError> {
+        let mut content = Vec::new();
+        //stop if we reach paragraph or program end
+        while !self.at(TokenKind::Oic) && !self.at(TokenKind::Kthxbye) && !self.at(TokenKind::Eof) {
+            //parse everything inside
+            let start = self.current_token.span.start;
+            let separator = self.separator();
+            match self.parse_inline() {
+                Ok(inline) => push_inline(&mut content, separator, inline),
+                Err(e) => {
+                    self.recover(e, start);
+                    if self.at(TokenKind::Mkay) {
+                        self.next_token();
+                    } else if self.at(TokenKind::Maek) {
+                        //next block starts, this paragraph was never closed
+                        break;
+                    }
+                }
+            }
+        }
+        Ok(content)
+    }
+
+    // <inline> ::= <variable-use> | <bold> | <italics> | <newline> | <audio> | <video> | <raw> | TEXT
+    //the one production for inline content, bold and italics call back into it so everything nests
+    fn parse_inline(&mut self) -> Result<Inline, CompileError> {
+
+        match self.current_token.kind {
+            //Case 1 var use (LEMMESEE MKAY)
+            TokenKind::Lemme => self.parse_variable_use(),
+
+            //Case 2: inline annotations
+            TokenKind::Gimmeh => {
+                // Look ahead one token to see which kind of annotation it is
+                let (next_kind, next_tok) = self.peek();
+
+                //caals parsers for different types
+                match next_kind {
+                    TokenKind::Bold => self.parse_bold(),
+                    TokenKind::Italics => self.parse_italics(),
+                    TokenKind::Soundz => self.parse_audio(),
+                    TokenKind::Vidz => self.parse_video(),
+                    TokenKind::Linkz => self.parse_link(),
+                    TokenKind::Pikchur => self.parse_image(),
+                    TokenKind::Code => self.parse_code(),
+                    TokenKind::Newline => self.parse_newline(),
+                    TokenKind::Raw => self.parse_raw(),
+                    _ => Err(self.syntax_error(format!("unrecognized #GIMMEH annotation '{}'", next_tok))),
+                }
+            }
+
+            // case 3: plain text
+            TokenKind::Text => self.parse_text(),
+            _ => Err(self.syntax_error(format!("unknown token '{}' inside inner-paragraph", self.current_token.text))),
+        }
+    }   
+
+    // <variable-define> ::= IHAZ VARDEF ITIZ TEXT MKAY | ε
+    //handles static variable definitions
+    fn parse_variable_define(&mut self) -> Result<Option<VariableDefine>, CompileError> {
+    if !self.at(TokenKind::I) {
+        return Ok(None);
+    }
+    self.next_token();
+
+    // expect HAZ instantly after #I
+    if !self.at(TokenKind::Haz) {
+        return Err(self.syntax_error(format!("expected 'HAZ' after #I, got '{}'", self.current_token.text)));
+    }
+    self.next_token();
+
+    // variable name enxt
+    if !self.at(TokenKind::Text) {
+        return Err(self.syntax_error(format!("expected variable name after HAZ, got '{}'", self.current_token.text)));
+    }
+    let name = self.next_token_taken();
+
+    // expect #IT
+    if !self.at(TokenKind::It) {
+        return Err(self.syntax_error(format!("expected #IT after variable name, got '{}'", self.current_token.text)));
+    }
+    self.next_token();
+
+    // expect IZ
+    if !self.at(TokenKind::Iz) {
+        return Err(self.syntax_error(format!("expected 'IZ' after #IT, got '{}'", self.current_token.text)));
+    }
+    self.next_token();
+
+    // variable value, every TEXT or quoted LITERAL up to #MKAY
+    if !self.at(TokenKind::Text) && !self.at(TokenKind::Literal) {
+        return Err(self.syntax_error(format!("expected value after IZ, got '{}'", self.current_token.text)));
+    }
+    let mut value = Vec::new();
+    while self.at(TokenKind::Text) || self.at(TokenKind::Literal) {
+        value.push(self.next_token_taken());
+    }
+
+    // expect #MKAY at end
+    if !self.at(TokenKind::Mkay) {
+        return Err(self.syntax_error(format!("var def must end with #MKAY, got '{}'", self.current_token.text)));
+    }
+    self.next_token();
+
+    //IMPORTANT: STORE VARIABLE FOR THE STATIC CHECK IN parse_variable_use
+    self.defined_variables.push(name.text.clone());
+    Ok(Some(VariableDefine { name, value }))
+    }
+    
+
+    //<variable-use> ::= LEMME SEE VAR_NAME MKAY
+    fn parse_variable_use(&mut self) -> Result<Inline, CompileError> {
+    if !self.at(TokenKind::Lemme) {
+        return Err(self.syntax_error("variable use must start with #LEMME SEE"));
+    }
+    self.next_token(); 
+
+    // Expect SEE after LEMME
+    if !self.at(TokenKind::See) {
+        return Err(self.syntax_error(format!("expected 'SEE' after #LEMME, got '{}'", self.current_token.text)));
+    }
+    self.next_token(); // skip SEE
+
+    //text
+    if !self.at(TokenKind::Text) {
+        return Err(self.syntax_error(format!("expected variable name after SEE, got '{}'", self.current_token.text)));
+    }
+    let name = self.next_token_taken();
+
+    // Static check against the scope chain, not a syntax problem so keep parsing
+    if let Err(e) = self.check_variable_defined(&name) {
+        self.diagnostics.push(e);
+    }
+
+    if !self.at(TokenKind::Mkay) {
+        return Err(self.syntax_error("the variable use must end with #MKAY"));
+    }
+    self.next_token();
+    Ok(Inline::VariableUse(VariableUse { name }))
+    }
+
+    //<bold> ::= GIMMEH BOLD <inline>+ MKAY
+    fn parse_bold(&mut self) -> Result<Inline, CompileError> {
+        if !self.at(TokenKind::Gimmeh) {
+            return Err(self.syntax_error("bold annotation must start with #GIMMEH"));
+        }
+        self.next_token();
+        if !self.at(TokenKind::Bold) {
+            return Err(self.syntax_error(format!("expected bold after #GIMMEH but found '{}'", self.current_token.text)));
+        }
+        self.next_token();
+
+        //needs some content before #MKAY
+        if self.at(TokenKind::Mkay) {
+            return Err(self.syntax_error(format!("expected TEXT after bold, but found '{}'", self.current_token.text)));
+        }
+        let content = self.parse_nested_inlines()?;
+
+        if !self.at(TokenKind::Mkay) {
+            return Err(self.syntax_error("bold annotation has to end with #MKAY"));
+        }
+        self.next_token();
+        Ok(Inline::Bold(content))
+    }
+
+    /*
+    <italics> ::= GIMMEH ITALICS <inline>+ MKAY
+    Same logic as parse_bold
+     */
+    fn parse_italics(&mut self) -> Result<Inline, CompileError> {
+        if !self.at(TokenKind::Gimmeh) {
+            return Err(self.syntax_error("italics annotation must start with #GIMMEH"));
+        }
+        self.next_token(); 
+
+        if !self.at(TokenKind::Italics) {
+            return Err(self.syntax_error(format!("expected ITALICS after #GIMMEH but found '{}'", self.current_token.text)));
+        }
+        self.next_token(); 
+
+        // Keep reading inline content until we hit #MKAY
+        if self.at(TokenKind::Mkay) {
+            return Err(self.syntax_error(format!("expected TEXT after italics, but found '{}'", self.current_token.text)));
+        }
+        let content = self.parse_nested_inlines()?;
+
+        if !self.at(TokenKind::Mkay) {
+            return Err(self.syntax_error("italics annotation has to end with #MKAY"));
+        }
+        self.next_token();
+        Ok(Inline::Italics(content))
+    }
+
+
+    //<heading> ::= GIMMEH HEADIN TEXT <inline>+ MKAY
+    //the level is only checked to be there, the SemanticAnalyzer checks its value
+    fn parse_heading(&mut self) -> Result<Heading, CompileError> {
+        if !self.at(TokenKind::Gimmeh) {
+            return Err(self.syntax_error("heading annotation must start with #GIMMEH"));
+        }
+        self.next_token();
+        if !self.at(TokenKind::Headin) {
+            return Err(self.syntax_error(format!("expected HEADIN after #GIMMEH but found '{}'", self.current_token.text)));
+        }
+        self.next_token();
+
+        if !self.at(TokenKind::Text) {
+            return Err(self.syntax_error(format!("expected heading level after HEADIN, but found '{}'", self.current_token.text)));
+        }
+        let level = self.next_token_taken();
+
+        if self.at(TokenKind::Mkay) {
+            return Err(self.syntax_error(format!("expected TEXT after heading level, but found '{}'", self.current_token.text)));
+        }
+        let content = self.parse_nested_inlines()?;
+        if !self.at(TokenKind::Mkay) {
+            return Err(self.syntax_error("heading annotation has to end with #MKAY"));
+        }
+        self.next_token();
+        Ok(Heading { level, content })
+    }
+
+    //<code-block> ::= MAEK CODEZ TEXT? CODE_TEXT OIC
+    //the lexer read everything up to #OIC raw, so there is only one token of code
+    fn parse_code_block(&mut self) -> Result<CodeBlock, CompileError> {
+        if !self.at(TokenKind::Maek) {
+            return Err(self.syntax_error("code block must start with #MAEK"));
+        }
+        self.next_token();
+        if !self.at(TokenKind::Codez) {
+            return Err(self.syntax_error(format!("expected CODEZ after #MAEK, but got '{}' instead", self.current_token.text)));
+        }
+        self.next_token();
+        let language = if self.at(TokenKind::Text) { Some(self.next_token_taken()) } else { None };
+        if !self.at(TokenKind::CodeText) {
+            return Err(self.syntax_error(format!("expected code after CODEZ, got '{}'", self.current_token.text)));
+        }
+        let code = self.next_token_taken();
+        if !self.at(TokenKind::Oic) {
+            return Err(self.syntax_error("code block must end with #OIC"));
+        }
+        self.next_token();
+        Ok(CodeBlock { language, code })
+    }
+
+    //<quote> ::= MAEK QUOTE <inline>* <cite>? OIC
+    //<cite> ::= GIMMEH CITE <inline>* MKAY, it has to be the last thing in the quote
+    fn parse_quote(&mut self) -> Result<Quote, CompileError> {
+        if !self.at(TokenKind::Maek) {
+            return Err(self.syntax_error("quote must start with #MAEK"));
+        }
+        self.next_token();
+        if !self.at(TokenKind::Quote) {
+            return Err(self.syntax_error(format!("expected QUOTE after #MAEK, but got '{}' instead", self.current_token.text)));
+        }
+        self.next_token();
+
+        let mut quote = Quote::default();
+        while !matches!(self.current_token.kind, TokenKind::Oic | TokenKind::Maek | TokenKind::Kthxbye | TokenKind::Eof) {
+            if self.at(TokenKind::Gimmeh) && self.peek().0 == TokenKind::Cite {
+                break;
+            }
+            let separator = self.separator();
+            let inline = self.parse_inline()?;
+            push_inline(&mut quote.content, separator, inline);
+        }
+
+        if self.at(TokenKind::Gimmeh) {
+            self.next_token();
+            self.next_token();
+            let cite = self.parse_nested_inlines()?;
+            if !self.at(TokenKind::Mkay) {
+                return Err(self.syntax_error("CITE has to end with #MKAY"));
+            }
+            self.next_token();
+            quote.cite = Some(cite);
+        }
+
+        if !self.at(TokenKind::Oic) {
+            return Err(self.syntax_error(format!("quote must end with #OIC, got '{}'", self.current_token.text)));
+        }
+        self.next_token();
+        Ok(quote)
+    }
+
+    //<line> ::= GIMMEH LINE MKAY
+    fn parse_line(&mut self) -> Result<Block, CompileError> {
+        if !self.at(TokenKind::Gimmeh) {
+            return Err(self.syntax_error("line annotation must start with #GIMMEH"));
+        }
+        self.next_token();
+        if !self.at(TokenKind::Line) {
+            return Err(self.syntax_error(format!("expected LINE after #GIMMEH, got '{}'", self.current_token.text)));
+        }
+        self.next_token();
+        if !self.at(TokenKind::Mkay) {
+            return Err(self.syntax_error(format!("line annotation must end with #MKAY, got '{}'", self.current_token.text)));
+        }
+        self.next_token();
+        Ok(Block::Line)
+    }
+
+    /*
+    <preformatted> ::= MAEK PREFORMATTD <inline>* OIC
+    Same inline content as a paragraph, but every bit of whitespace is kept as written.
+    The line break right after PREFORMATTD and the whitespace in front of #OIC are left out.
+     */
+    fn parse_preformatted(&mut self) -> Result<Block, CompileError> {
+        if !self.at(TokenKind::Maek) {
+            return Err(self.syntax_error("preformatted block must start with #MAEK"));
+        }
+        self.next_token();
+        if !self.at(TokenKind::Preformattd) {
+            return Err(self.syntax_error(format!("expected PREFORMATTD after #MAEK, but got '{}' instead", self.current_token.text)));
+        }
+        self.next_token();
+
+        self.preformatted = true;
+        let mut content = Vec::new();
+        //indentation of the first line counts, the line break before it doesn't
+        let indent = match self.separator() {
+            Some(Inline::Text(mut first)) => {
+                if let Some(newline) = first.text.find('\n') {
+                    first.text.drain(..=newline);
+                }
+                Some(first).filter(|first| !first.text.is_empty())
+            }
+            _ => None,
+        };
+        let mut result = Ok(());
+        while !matches!(self.current_token.kind, TokenKind::Oic | TokenKind::Maek | TokenKind::Kthxbye | TokenKind::Eof) {
+            let separator = self.separator();
+            match self.parse_inline() {
+                Ok(inline) => push_inline(&mut content, separator, inline),
+                Err(e) => {
+                    result = Err(e);
+                    break;
+                }
+            }
+        }
+        self.preformatted = false;
+        result?;
+        if let Some(indent) = indent.filter(|_| !content.is_empty()) {
+            content.insert(0, Inline::Text(indent));
+        }
+
+        if !self.at(TokenKind::Oic) {
+            return Err(self.syntax_error(format!("preformatted block must end with #OIC, got '{}'", self.current_token.text)));
+        }
+        self.next_token();
+        Ok(Block::Preformatted(content))
+    }
+
+    //<table> ::= MAEK TABL <header-row>? <row>* OIC
+    //a broken row is skipped up to its #OIC so the rest of the table still parses
+    fn parse_table(&mut self) -> Result<Table, CompileError> {
+        if !self.at(TokenKind::Maek) {
+            return Err(self.syntax_error("table annotation must start with #MAEK"));
+        }
+        self.next_token();
+        if !self.at(TokenKind::Tabl) {
+            return Err(self.syntax_error(format!("expected TABL after #MAEK, but got '{}' instead", self.current_token.text)));
+        }
+        self.next_token();
+
+        let mut table = Table::default();
+        while self.at(TokenKind::Maek) && matches!(self.peek().0, TokenKind::Hedr | TokenKind::Row) {
+            let start = self.current_token.span.start;
+            match self.parse_table_row() {
+                //only the first row can be the header
+                Ok(row) if row.keyword.kind == TokenKind::Hedr => {
+                    if table.header.is_some() || !table.rows.is_empty() {
+                        let message = "the HEDR row has to be the first row of the table";
+                        let error = CompileError::Syntax(Diagnostic::new(&self.lexer.file_name, row.keyword.position(), &row.keyword.text, message));
+                        self.diagnostics.push(error);
+                    }
+                    table.header = Some(row);
+                }
+                Ok(row) => table.rows.push(row),
+                Err(e) => {
+                    self.recover(e, start);
+                    while !matches!(self.current_token.kind, TokenKind::Oic | TokenKind::Maek | TokenKind::Kthxbye | TokenKind::Eof) {
+                        self.next_token();
+                    }
+                    if self.at(TokenKind::Oic) {
+                        self.next_token();
+                    }
+                }
+            }
+        }
+
+        if !self.at(TokenKind::Oic) {
+            return Err(self.syntax_error(format!("table annotation must end with #OIC, got '{}'", self.current_token.text)));
+        }
+        self.next_token();
+        Ok(table)
+    }
+
+    //<header-row> ::= MAEK HEDR <cell>* OIC, <row> ::= MAEK ROW <cell>* OIC
+    fn parse_table_row(&mut self) -> Result<TableRow, CompileError> {
+        if !self.at(TokenKind::Maek) {
+            return Err(self.syntax_error("table row must start with #MAEK"));
+        }
+        self.next_token();
+        if !self.at(TokenKind::Hedr) && !self.at(TokenKind::Row) {
+            return Err(self.syntax_error(format!("expected ROW or HEDR after #MAEK, but got '{}' instead", self.current_token.text)));
+        }
+        let keyword = self.next_token_taken();
+
+        let mut cells = Vec::new();
+        while self.at(TokenKind::Gimmeh) {
+            cells.push(self.parse_table_cell()?);
+        }
+        if !self.at(TokenKind::Oic) {
+            return Err(self.syntax_error(format!("expected #GIMMEH CELL or #OIC inside the row, got '{}'", self.current_token.text)));
+        }
+        self.next_token();
+        Ok(TableRow { keyword, cells })
+    }
+
+    //<cell> ::= GIMMEH CELL <inline>* MKAY
+    fn parse_table_cell(&mut self) -> Result<Vec<Inline>, CompileError> {
+        if !self.at(TokenKind::Gimmeh) {
+            return Err(self.syntax_error("table cell must start with #GIMMEH"));
+        }
+        self.next_token();
+        if !self.at(TokenKind::Cell) {
+            return Err(self.syntax_error(format!("expected CELL after #GIMMEH, got '{}'", self.current_token.text)));
+        }
+        self.next_token();
+        let content = self.parse_nested_inlines()?;
+        if !self.at(TokenKind::Mkay) {
+            return Err(self.syntax_error("table cell must end with #MKAY"));
+        }
+        self.next_token();
+        Ok(content)
+    }
+
+    //<list> ::= MAEK LIST <list-items> OIC | MAEK NUMBRD LIST <list-items> OIC
+    fn parse_list(&mut self) -> Result<List, CompileError> {
+        if !self.at(TokenKind::Maek) {
+            return Err(self.syntax_error("List annotation must start wuth #MAEK"));
+        }
+        self.next_token();
+        //optional NUMBRD for an ordered list
+        let ordered = self.at(TokenKind::Numbrd);
+        if ordered {
+            self.next_token();
+        }
+        if !self.at(TokenKind::List) {
+            return Err(self.syntax_error(format!("expected LIST after #MAEK, but got '{}' instead", self.current_token.text)));
+        }
+        self.next_token();
+        //parse list items
+        let items = self.parse_list_items()?;
+        if !self.at(TokenKind::Oic) {
+            return Err(self.syntax_error("list annotation must end with #OIC"));
+        }
+        self.next_token();
+        Ok(List { ordered, items })
+    }
+
+
+    //<list-items> ::= <list-item> <list-items> | ε
+    fn parse_list_items(&mut self) -> Result<Vec<ListItem>, CompileError> {
+        let mut items = Vec::new();
+
+        //stop when list or program ends
+        while !self.at(TokenKind::Oic) && !self.at(TokenKind::Kthxbye) && !self.at(TokenKind::Eof) {
+            let start = self.current_token.span.start;
+            match self.parse_list_item() {
+                Ok(item) => items.push(item),
+                Err(e) => {
+                    self.recover(e, start);
+                    if self.at(TokenKind::Mkay) {
+                        self.next_token();
+                    } else if self.at(TokenKind::Maek) {
+                        //next block starts, this list was never closed
+                        break;
+                    }
+                }
+            }
+        }
+        Ok(items)
+    }
+
+    //<list-item> ::= GIMMEH ITEM <inner-list> MKAY
+    fn parse_list_item(&mut self) -> Result<ListItem, CompileError> {
+        if !self.at(TokenKind::Gimmeh) {
+            return Err(self.syntax_error(format!("expected #GIMMEH ITEM inside the lis, '{}' instead", self.current_token.text)));
+        }
+        self.next_token();
+        if !self.at(TokenKind::Item) {
+            return Err(self.syntax_error(format!("expected item after #GIMMEH, got '{}'", self.current_token.text)));
+        }
+        self.next_token();
+        //inner text of list
+        let content = self.parse_inner_list()?;
+        //then maybe a nested list
+        let sublist = match (self.current_token.kind, self.peek().0) {
+            (TokenKind::Maek, TokenKind::List | TokenKind::Numbrd) => Some(self.parse_list()?),
+            _ => None,
+        };
+        if !self.at(TokenKind::Mkay) {
+            return Err(self.syntax_error("list item must end with #MKAY"));
+        }
+        self.next_token();
+        Ok(ListItem { content, sublist })
+    }
+
+    //<inner-list> ::= <inline> <inner-list> | ε
+    //any inline content, up to the #MKAY that closes the item
+    fn parse_inner_list(&mut self) -> Result<Vec<Inline>, CompileError> {
+        self.parse_nested_inlines()
+    }
+
+    //<audio> ::= GIMMEH SOUNDZ ADDRESS MKAY
+    fn parse_audio(&mut self) -> Result<Inline, CompileError> {
+        if !self.at(TokenKind::Gimmeh) {
+            return Err(self.syntax_error("audio annotation must start with #GIMMEH"));
+        }
+        self.next_token();
+        if !self.at(TokenKind::Soundz) {
+            return Err(self.syntax_error(format!("expected SOUNDZ after GIMMEH, got '{}' instead", self.current_token.text)));
+        }
+        self.next_token();
+        //literal file path/url
+        let address = self.parse_address("SOUNDZ")?;
+        if !self.at(TokenKind::Mkay) {
+            return Err(self.syntax_error("audio annotation must end with #MKAY"));
+        }
+        self.next_token();
+        Ok(Inline::Audio(address))
+    }
+
+    //<video> ::= GIMMEH VIDZ ADDRESS MKAY
+    fn parse_video(&mut self) -> Result<Inline, CompileError> {
+        if !self.at(TokenKind::Gimmeh) {
+            return Err(self.syntax_error("video annotation must start with #GIMMEH"));
+        }
+        self.next_token();
+        if !self.at(TokenKind::Vidz) {
+            return Err(self.syntax_error(format!("expected VIDZ after GIMMEH, got '{}' instead", self.current_token.text)));
+        }
+        self.next_token();
+        let address = self.parse_address("VIDZ")?;
+        if !self.at(TokenKind::Mkay) {
+            return Err(self.syntax_error("video annotation must end with #MKAY"));
+        }
+        self.next_token();
+        Ok(Inline::Video(address))
+    }
+
+    //<link> ::= GIMMEH LINKZ ADDRESS <inline>* MKAY
+    fn parse_link(&mut self) -> Result<Inline, CompileError> {
+        if !self.at(TokenKind::Gimmeh) {
+            return Err(self.syntax_error("link annotation must start with #GIMMEH"));
+        }
+        if self.in_link {
+            return Err(self.syntax_error("links can't be nested, LINKZ is not allowed inside link text"));
+        }
+        self.next_token();
+        if !self.at(TokenKind::Linkz) {
+            return Err(self.syntax_error(format!("expected LINKZ after GIMMEH, got '{}' instead", self.current_token.text)));
+        }
+        self.next_token();
+        let address = self.parse_address("LINKZ")?;
+        //link text, bold/italics/variables nest like everywhere else, other links don't
+        self.in_link = true;
+        let content = self.parse_nested_inlines();
+        self.in_link = false;
+        let content = content?;
+        if !self.at(TokenKind::Mkay) {
+            return Err(self.syntax_error("link annotation must end with #MKAY"));
+        }
+        self.next_token();
+        Ok(Inline::Link { address, content })
+    }
+
+    //<image> ::= GIMMEH PIKCHUR ADDRESS TEXT* MKAY
+    //alt text is plain words, a missing one is only a warning (see SemanticAnalyzer)
+    fn parse_image(&mut self) -> Result<Inline, CompileError> {
+        if !self.at(TokenKind::Gimmeh) {
+            return Err(self.syntax_error("image annotation must start with #GIMMEH"));
+        }
+        self.next_token();
+        if !self.at(TokenKind::Pikchur) {
+            return Err(self.syntax_error(format!("expected PIKCHUR after GIMMEH, got '{}' instead", self.current_token.text)));
+        }
+        self.next_token();
+        let address = self.parse_address("PIKCHUR")?;
+        let mut alt = Vec::new();
+        while self.at(TokenKind::Text) {
+            alt.push(self.next_token_taken());
+        }
+        if !self.at(TokenKind::Mkay) {
+            return Err(self.syntax_error(format!("image annotation must end with #MKAY, got '{}'", self.current_token.text)));
+        }
+        self.next_token();
+        Ok(Inline::Image { address, alt })
+    }
+
+    //<code> ::= GIMMEH CODE CODE_TEXT MKAY
+    fn parse_code(&mut self) -> Result<Inline, CompileError> {
+        self.next_token();
+        if !self.at(TokenKind::Code) {
+            return Err(self.syntax_error(format!("expected 'CODE' after #GIMMEH, got '{}'", self.current_token.text)));
+        }
+        self.next_token();
+        if !self.at(TokenKind::CodeText) {
+            return Err(self.syntax_error(format!("expected code after CODE, got '{}'", self.current_token.text)));
+        }
+        let code = self.next_token_taken();
+        if !self.at(TokenKind::Mkay) {
+            return Err(self.syntax_error("inline code has to end with #MKAY"));
+        }
+        self.next_token();
+        Ok(Inline::Code(code))
+    }
+
+    //Parses manual line break annotations
+    fn parse_newline(&mut self) -> Result<Inline, CompileError> {
+        self.next_token(); 
+
+        //next token must literally be "NEWLINE"
+        if !self.at(TokenKind::Newline) {
+            return Err(self.syntax_error(format!("expected 'NEWLINE' after #GIMMEH, got '{}'", self.current_token.text)));
+        }
+        self.next_token();
+        Ok(Inline::Newline)
+    }
+
+    //<raw> ::= GIMMEH RAW TEXT MKAY
+    //the lexer already turned everything up to #MKAY into TEXT
+    fn parse_raw(&mut self) -> Result<Inline, CompileError> {
+        self.next_token();
+        if !self.at(TokenKind::Raw) {
+            return Err(self.syntax_error(format!("expected 'RAW' after #GIMMEH, got '{}'", self.current_token.text)));
+        }
+        self.next_token();
+
+        let mut words = Vec::new();
+        while self.at(TokenKind::Text) {
+            words.push(self.next_token_taken());
+        }
+        if !self.at(TokenKind::Mkay) {
+            return Err(self.syntax_error("raw text has to end with #MKAY"));
+        }
+        self.next_token();
+        Ok(Inline::Raw(words))
+    }
+
+    //parses raw text
+    fn parse_text(&mut self) -> Result<Inline, CompileError> {
+        //text annotations dont begin with #
+        if !self.at(TokenKind::Text) {
+            return Err(self.syntax_error(format!("expected TEXT token, found '{}'", self.current_token.text)));
+        }
+        Ok(Inline::Text(self.next_token_taken()))
+    }
+}
+
+
+//adds an inline item, after the source whitespace in front of it unless it is the first one
+fn push_inline(content: &mut Vec<Inline>, separator: Option<Inline>, inline: Inline) {
+    if let Some(separator) = separator.filter(|_| !content.is_empty()) {
+        content.push(separator);
+    }
+    content.push(inline);
+}
+
+
+//compiler implementation. tokenizes using lexical, parses into an AST using syntax, walks the AST using semantic
+impl Compiler for MarkdownSyntaxAnalyzer {
+        fn compile(&mut self, source: &str) -> Result<String, CompileError> {
+
+        //nothing from an earlier compile carries over when the analyzer is reused
+        self.current_token = Token::default();
+        self.defined_variables.clear();
+        self.scope_starts.clear();
+        self.diagnostics.clear();
+        self.preformatted = false;
+        self.in_link = false;
+        self.warnings.clear();
+
+        //lexical analysis
+        self.lexer = MarkdownLexicalAnalyzer::new(source);
+        self.lexer.file_name = self.file_name.clone();
+        self.lexer.tokenize()?;
+
+        //pop first availble token for parser to start
+        self.next_token();
+        //parse into the AST
+        let document = self.parse_lolcode()?;
+
+        //semantic checks and html generation both walk the AST
+        let mut sem = SemanticAnalyzer::new(&self.file_name);
+        sem.safe_mode = self.safe_mode;
+        let html = sem.convert_html(&document);
+        self.warnings = std::mem::take(&mut sem.warnings);
+        html
+    }
+
+
+    /*
+    THe compilation logic is mostly done by the lexical, syntax, and semantic analyzer
+    I don't think I structured the compiler design correctly, so this is delegated elsewhere
+     */
+    fn next_token(&mut self) -> Token {
+        self.lexer.tokens.pop().unwrap_or_else(|| self.lexer.eof_token())
+    }
+
+
+    fn parse(&mut self) -> Result<Document, CompileError> {
+        self.parse_lolcode()
+    }
+
+    fn current_token(&self) -> Token {
+        self.current_token.clone()
+    }
+
+    fn set_current_token(&mut self, tok: Token) {
+        self.current_token = tok;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    const HEAD: &str = "#HAI #MAEK HEAD #GIMMEH TITLE t #MKAY #OIC ";
+
+    //compiles a body that should fail and gives back where each error was found
+    fn error_positions(body: &str) -> Vec<(usize, usize)> {
+        let source = format!("{}{} #KTHXBYE", HEAD, body);
+        let err = MarkdownSyntaxAnalyzer::new().compile(&source).expect_err("body has errors");
+        let errors = match err {
+            CompileError::Multiple(errors) => errors,
+            single => vec![single],
+        };
+        errors.iter().map(|e| e.diagnostic().map(|d| (d.line, d.column)).unwrap()).collect()
+    }
+
+    #[test]
+    fn every_independent_error_is_reported() {
+        let body = "#MAEK PARAGRAF one #GIMMEH BOGUS #MKAY two #LEMME SEE nope #MKAY three #OIC
+#MAEK LIST #GIMMEH ITEM a #MKAY #GIMMEH BOLD b #MKAY #GIMMEH ITEM c #MKAY #OIC
+#MAEK TABL #MAEK ROW #GIMMEH CELL a #MKAY #OIC #MAEK ROW #GIMMEH ITEM x #MKAY #OIC #OIC
+#MKAY
+#GIMMEH HEADIN 2 fine #MKAY";
+        assert_eq!(error_positions(body).len(), 5);
+    }
+
+    #[test]
+    fn paragraph_resyncs_after_each_error() {
+        let body = "#MAEK PARAGRAF a #GIMMEH BOGUS #MKAY b #GIMMEH NOPE #MKAY c #OIC";
+        assert_eq!(error_positions(body), vec![(1, 61), (1, 83)]);
+    }
+
+    #[test]
+    fn list_resyncs_at_the_next_item() {
+        let body = "#MAEK LIST #GIMMEH BOLD a #MKAY #GIMMEH ITEM b #MKAY #GIMMEH TITLE c #MKAY #OIC";
+        assert_eq!(error_positions(body).len(), 2);
+    }
+
+    #[test]
+    fn table_resyncs_at_the_next_row() {
+        let body = "#MAEK TABL #MAEK ROW #GIMMEH ITEM a #MKAY #OIC #MAEK ROW #GIMMEH CELL b #MKAY #OIC #MAEK ROW #GIMMEH BOLD c #MKAY #OIC #OIC";
+        assert_eq!(error_positions(body).len(), 2);
+    }
+
+    #[test]
+    fn body_resyncs_after_stray_keywords() {
+        let body = "#MKAY #MAEK PARAGRAF fine #OIC #OIC";
+        assert_eq!(error_positions(body).len(), 2);
+    }
+
+    //compiles a body that should fail and gives back the first error message
+    fn first_message(body: &str) -> String {
+        let source = format!("{}{} #KTHXBYE", HEAD, body);
+        let err = MarkdownSyntaxAnalyzer::new().compile(&source).expect_err("body has errors");
+        err.diagnostic().map(|d| d.message.clone()).unwrap()
+    }
+
+    #[test]
+    fn only_definitions_still_in_scope_count_as_later() {
+        let sibling = "#MAEK PARAGRAF #LEMME SEE x #MKAY #OIC #MAEK PARAGRAF #I HAZ x #IT IZ 1 #MKAY #OIC";
+        assert_eq!(first_message(sibling), "variable 'x' is never defined");
+        let global = "#MAEK PARAGRAF #LEMME SEE x #MKAY #OIC #I HAZ x #IT IZ 1 #MKAY";
+        assert!(first_message(global).starts_with("variable 'x' used before definition"));
+        let outside = "#LEMME SEE x #MKAY #MAEK PARAGRAF #I HAZ x #IT IZ 1 #MKAY #OIC";
+        assert_eq!(first_message(outside), "variable 'x' is never defined");
+    }
+
+    #[test]
+    fn links_inside_link_text_are_rejected() {
+        let body = "#GIMMEH LINKZ a.html x #GIMMEH BOLD #GIMMEH LINKZ b.html y #MKAY #MKAY #MKAY";
+        assert_eq!(error_positions(body).first(), Some(&(1, 80)));
+        let body = "#GIMMEH LINKZ a.html x #MKAY #GIMMEH LINKZ b.html #GIMMEH BOLD y #MKAY #MKAY";
+        let source = format!("{}{} #KTHXBYE", HEAD, body);
+        assert!(MarkdownSyntaxAnalyzer::new().compile(&source).is_ok());
+    }
+
+    #[test]
+    fn reused_analyzer_forgets_earlier_variables() {
+        let mut compiler = MarkdownSyntaxAnalyzer::new();
+        compiler
+            .compile("#HAI #MAEK HEAD #GIMMEH TITLE a #MKAY #OIC #I HAZ x #IT IZ hi #MKAY #LEMME SEE x #MKAY #KTHXBYE")
+            .expect("x is defined");
+        let err = compiler
+            .compile("#HAI #MAEK HEAD #GIMMEH TITLE b #MKAY #OIC #LEMME SEE x #MKAY #KTHXBYE")
+            .unwrap_err();
+        assert!(err.to_string().contains("variable 'x' is never defined"), "{}", err);
+    }
+}